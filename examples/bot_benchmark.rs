@@ -0,0 +1,21 @@
+//! Plays headless games with the bot and reports its win rate per difficulty
+//!
+//! `cargo run --release --example bot_benchmark -- [games] [first seed]`
+use mine::bot::run_games;
+use mine::resources::difficulty::Difficulty;
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let games = args.next().and_then(|a| a.parse().ok()).unwrap_or(100);
+    let first_seed = args.next().and_then(|a| a.parse().ok()).unwrap_or(0);
+    for difficulty in Difficulty::ALL {
+        let report = run_games(difficulty, games, first_seed);
+        println!(
+            "{:<12} {:>5}/{:<5} won ({:.1}%)",
+            difficulty.label(),
+            report.won,
+            report.played,
+            report.win_rate() * 100.
+        );
+    }
+}
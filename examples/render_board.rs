@@ -0,0 +1,30 @@
+//! Prints a board layout with the debug console renderer
+//!
+//! `cargo run --features debug --example render_board -- <board.mbf | grid.txt | code>`
+use std::fs;
+
+use mine::Map;
+
+fn main() {
+    let Some(arg) = std::env::args().nth(1) else {
+        eprintln!("usage: render_board <board.mbf | grid.txt | code>");
+        std::process::exit(2);
+    };
+    let map = match fs::read(&arg) {
+        Ok(bytes) if arg.ends_with(".mbf") => Map::from_mbf(&bytes).map_err(|e| e.to_string()),
+        Ok(bytes) => {
+            Map::from_text_grid(&String::from_utf8_lossy(&bytes)).map_err(|e| e.to_string())
+        }
+        // Not a file, maybe a board code
+        Err(_) => Map::decode(&arg)
+            .map(|(map, _)| map)
+            .map_err(|e| e.to_string()),
+    };
+    match map {
+        Ok(map) => println!("{}", map.console_output()),
+        Err(e) => {
+            eprintln!("failed to read {}: {}", arg, e);
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,272 @@
+use crate::components::coordinates::Coordinates;
+use crate::resources::board::GameStatus;
+use crate::resources::board_options::BoardOptions;
+use crate::resources::difficulty::Difficulty;
+use crate::resources::map::Map;
+use crate::resources::tile::Tile;
+use crate::solver::deduction::deduce;
+use crate::solver::VisibleBoard;
+
+use super::strategy::{next_moves, BotMove};
+
+/// Game played without any Bevy app, following the same rules as the board
+/// systems: flood fill of empty tiles, chords on satisfied numbers and a win
+/// once every safe tile is uncovered
+#[derive(Debug)]
+pub struct HeadlessGame {
+    map: Map,
+    covered: Vec<bool>,
+    flagged: Vec<bool>,
+    lives: u8,
+    status: GameStatus,
+}
+
+impl HeadlessGame {
+    /// Generates the same map as `BoardPlugin::create_board` for the given
+    /// options and seed, including the safe start
+    pub fn new(options: &BoardOptions, seed: u64) -> Self {
+        let mut map = options.empty_map();
+        map.set_bombs(options.boom_count, seed);
+        let start = options.safe_place.then(|| {
+            map.iter()
+                .find(|(_, tile)| **tile == Tile::Empty)
+                .map(|(coords, _)| coords)
+        });
+        let mut game = Self::from_map(map, start.flatten());
+        game.lives = options.lives.max(1);
+        game
+    }
+
+    /// Starts a game on a given layout, uncovering `start` first
+    pub fn from_map(map: Map, start: Option<Coordinates>) -> Self {
+        let len = map.width() as usize * map.height() as usize;
+        // Void tiles have nothing to uncover
+        let covered = map.iter().map(|(_, tile)| *tile != Tile::Void).collect();
+        let mut game = Self {
+            map,
+            covered,
+            flagged: vec![false; len],
+            lives: 1,
+            status: GameStatus::Playing,
+        };
+        if let Some(coords) = start {
+            game.reveal(coords);
+        }
+        game
+    }
+
+    pub fn status(&self) -> GameStatus {
+        self.status
+    }
+
+    /// What the bot is allowed to see
+    pub fn visible(&self) -> VisibleBoard {
+        VisibleBoard::from_map(
+            &self.map,
+            |c| self.covered[self.index(c)],
+            |c| self.flagged[self.index(c)],
+        )
+    }
+
+    /// Applies a move, ignored once the game is over
+    pub fn apply(&mut self, bot_move: BotMove) {
+        if self.status != GameStatus::Playing {
+            return;
+        }
+        match bot_move {
+            BotMove::Reveal(coords) => self.reveal(coords),
+            BotMove::Flag(coords) => {
+                let i = self.index(coords);
+                if self.covered[i] {
+                    self.flagged[i] = !self.flagged[i];
+                }
+            }
+            BotMove::Chord(coords) => self.chord(coords),
+        }
+    }
+
+    /// Lets the bot play until the game is over, returning whether it won
+    pub fn play(&mut self) -> bool {
+        while self.status == GameStatus::Playing {
+            let moves = next_moves(&self.visible());
+            if moves.is_empty() {
+                break;
+            }
+            for bot_move in moves {
+                self.apply(bot_move);
+            }
+        }
+        self.status == GameStatus::Won
+    }
+
+    /// Only uncovers the tiles proven safe, returning whether that was
+    /// enough to win: a board solvable without any guess
+    pub fn play_without_guessing(&mut self) -> bool {
+        while self.status == GameStatus::Playing {
+            let safe: Vec<_> = deduce(&self.visible())
+                .into_iter()
+                .filter(|d| !d.mine)
+                .collect();
+            if safe.is_empty() {
+                break;
+            }
+            let visible = self.visible();
+            for deduction in safe {
+                self.reveal(visible.coords(deduction.cell));
+            }
+        }
+        self.status == GameStatus::Won
+    }
+
+    /// Safe tiles which are still covered
+    pub fn covered_safe_tiles(&self) -> usize {
+        self.map
+            .iter()
+            .filter(|(c, tile)| !tile.is_bomb() && self.covered[self.index(*c)])
+            .count()
+    }
+
+    fn reveal(&mut self, coords: Coordinates) {
+        let mut stack = vec![coords];
+        while let Some(coords) = stack.pop() {
+            let i = self.index(coords);
+            if !self.covered[i] || self.flagged[i] {
+                continue;
+            }
+            self.covered[i] = false;
+            match self.map.get(coords) {
+                Some(Tile::Bomb(_)) => {
+                    self.lives -= 1;
+                    if self.lives == 0 {
+                        self.status = GameStatus::Lost;
+                        return;
+                    }
+                }
+                Some(Tile::Empty) => stack.extend(self.map.neighbors(coords)),
+                _ => (),
+            }
+        }
+        let completed = self
+            .map
+            .iter()
+            .all(|(c, tile)| tile.is_bomb() || !self.covered[self.index(c)]);
+        if completed {
+            self.status = GameStatus::Won;
+        }
+    }
+
+    fn chord(&mut self, coords: Coordinates) {
+        let number = match self.map.get(coords) {
+            Some(Tile::BombNeighbor(v)) if !self.covered[self.index(coords)] => *v as usize,
+            _ => return,
+        };
+        let neighbors: Vec<_> = self.map.neighbors(coords).collect();
+        // Exploded bombs count as flags
+        let flags = neighbors
+            .iter()
+            .filter(|c| {
+                let i = self.index(**c);
+                self.flagged[i] || (!self.covered[i] && self.map.is_bomb_at(**c))
+            })
+            .count();
+        if flags != number {
+            return;
+        }
+        for neighbor in neighbors {
+            self.reveal(neighbor);
+            if self.status != GameStatus::Playing {
+                return;
+            }
+        }
+    }
+
+    fn index(&self, coords: Coordinates) -> usize {
+        coords.y as usize * self.map.width() as usize + coords.x as usize
+    }
+}
+
+/// Outcome of a batch of headless games
+#[derive(Debug, Clone, Copy)]
+pub struct BotReport {
+    pub difficulty: Difficulty,
+    pub played: u32,
+    pub won: u32,
+}
+
+impl BotReport {
+    /// Ratio of won games, `0` when nothing was played
+    pub fn win_rate(&self) -> f32 {
+        if self.played == 0 {
+            0.
+        } else {
+            self.won as f32 / self.played as f32
+        }
+    }
+}
+
+/// Plays `games` boards of the given difficulty, seeded from `first_seed`
+/// onwards so a run can be reproduced
+pub fn run_games(difficulty: Difficulty, games: u32, first_seed: u64) -> BotReport {
+    let options = difficulty.board_options();
+    let won = (0..games)
+        .filter(|i| HeadlessGame::new(&options, first_seed.wrapping_add(*i as u64)).play())
+        .count() as u32;
+    BotReport {
+        difficulty,
+        played: games,
+        won,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bot_finishes_every_game() {
+        let options = Difficulty::Beginner.board_options();
+        for seed in 0..20 {
+            let mut game = HeadlessGame::new(&options, seed);
+            game.play();
+            assert_ne!(game.status(), GameStatus::Playing);
+        }
+    }
+
+    #[test]
+    fn bot_wins_most_beginner_games() {
+        let report = run_games(Difficulty::Beginner, 50, 0);
+        assert_eq!(report.played, 50);
+        assert!(report.win_rate() > 0.5, "win rate {}", report.win_rate());
+    }
+
+    #[test]
+    fn no_guess_check() {
+        // Opening on the left reveals the column of 1s and proves the bombs
+        let map = Map::from_text_grid("..*\n..*\n...\n").unwrap();
+        assert!(HeadlessGame::from_map(map.clone(), Some((0, 0).into())).play_without_guessing());
+        // Two bombs in a 2*2 corner with two numbers: a coin flip
+        let map = Map::from_text_grid("*.\n.*\n").unwrap();
+        let mut game = HeadlessGame::from_map(map, None);
+        assert!(!game.play_without_guessing());
+        assert_eq!(game.covered_safe_tiles(), 2);
+    }
+
+    #[test]
+    fn shaped_boards_are_won_without_the_void() {
+        // The hole in the corner is never uncovered
+        let map = Map::from_text_grid("#..\n...\n..*\n").unwrap();
+        assert!(HeadlessGame::from_map(map, Some((0, 0).into())).play_without_guessing());
+    }
+
+    #[test]
+    fn exploded_bombs_count_as_resolved() {
+        // More lives than bombs, every game ends up won
+        let options = BoardOptions {
+            lives: 11,
+            ..Difficulty::Beginner.board_options()
+        };
+        for seed in 0..10 {
+            assert!(HeadlessGame::new(&options, seed).play());
+        }
+    }
+}
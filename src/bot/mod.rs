@@ -0,0 +1,129 @@
+//! Bot playing minesweeper through the same events as a human player, or
+//! headlessly to measure how often it wins
+pub mod headless;
+pub mod strategy;
+
+pub use headless::{run_games, BotReport, HeadlessGame};
+pub use strategy::{next_moves, BotMove};
+
+use std::time::Duration;
+
+use bevy::log;
+use bevy::prelude::*;
+
+use crate::resources::board::Board;
+use crate::solver::{self, VisibleBoard};
+use crate::systems::event::{TileChordEvent, TileMarkEvent, TileTriggerEvent};
+
+const MIN_DELAY: Duration = Duration::from_millis(10);
+const MAX_DELAY: Duration = Duration::from_secs(2);
+// Bot ticks a move may take to show up on the board before moving again
+const MAX_WAIT_TICKS: u32 = 10;
+
+/// Bot state, toggled with `B` and sped up or slowed down with `+` and `-`
+#[derive(Debug, Resource)]
+pub struct Bot {
+    pub enabled: bool,
+    /// Time between two moves
+    pub delay: Duration,
+    timer: Timer,
+    // Covered and flagged tile counts when the last move was sent
+    last_move: Option<(usize, usize)>,
+    waited_ticks: u32,
+}
+
+impl Default for Bot {
+    fn default() -> Self {
+        let delay = Duration::from_millis(250);
+        Self {
+            enabled: false,
+            delay,
+            timer: Timer::new(delay, TimerMode::Repeating),
+            last_move: None,
+            waited_ticks: 0,
+        }
+    }
+}
+
+impl Bot {
+    pub fn set_delay(&mut self, delay: Duration) {
+        self.delay = delay.clamp(MIN_DELAY, MAX_DELAY);
+        self.timer.set_duration(self.delay);
+    }
+}
+
+/// Bot plugin, the bot only plays while the app is in `running_state`
+pub struct BotPlugin<T> {
+    pub running_state: T,
+}
+
+impl<T: States> Plugin for BotPlugin<T> {
+    fn build(&self, app: &mut App) {
+        let running = || in_state(self.running_state.clone());
+        app.init_resource::<Bot>()
+            .add_systems(Update, bot_controls.run_if(running())) // 开关与速度
+            .add_systems(Update, bot_play.run_if(running())); // 自动游戏
+
+        log::info!("Loaded Bot Plugin");
+    }
+}
+
+pub fn bot_controls(keys: Res<Input<KeyCode>>, mut bot: ResMut<Bot>) {
+    if keys.just_pressed(KeyCode::B) {
+        bot.enabled = !bot.enabled;
+        log::info!("Bot {}", if bot.enabled { "enabled" } else { "disabled" });
+    }
+    if keys.any_just_pressed([KeyCode::Equals, KeyCode::NumpadAdd]) {
+        let delay = bot.delay / 2;
+        bot.set_delay(delay);
+        log::info!("Bot delay {:?}", bot.delay);
+    }
+    if keys.any_just_pressed([KeyCode::Minus, KeyCode::NumpadSubtract]) {
+        let delay = bot.delay * 2;
+        bot.set_delay(delay);
+        log::info!("Bot delay {:?}", bot.delay);
+    }
+}
+
+pub fn bot_play(
+    time: Res<Time>,
+    mut bot: ResMut<Bot>,
+    board: Option<ResMut<Board>>,
+    mut trigger_event_wr: EventWriter<TileTriggerEvent>,
+    mut mark_event_wr: EventWriter<TileMarkEvent>,
+    mut chord_event_wr: EventWriter<TileChordEvent>,
+) {
+    let mut board = match board {
+        Some(b) if bot.enabled && b.is_playing() => b,
+        _ => return,
+    };
+    if !solver::supports(&board.tile_map) {
+        log::info!("The bot only plays boards with a mine per tile");
+        bot.enabled = false;
+        return;
+    }
+    if !bot.timer.tick(time.delta()).finished() {
+        return;
+    }
+    // Events are handled over the next frames: moving again on a stale board
+    // would replay the same move, and toggle a flag back off
+    let state = (board.covered_tiles.len(), board.flagged_tiles.len());
+    if bot.last_move == Some(state) && bot.waited_ticks < MAX_WAIT_TICKS {
+        bot.waited_ticks += 1;
+        return;
+    }
+    let Some(bot_move) = next_moves(&VisibleBoard::from_board(&board))
+        .first()
+        .copied()
+    else {
+        return;
+    };
+    match bot_move {
+        BotMove::Reveal(coords) => trigger_event_wr.send(TileTriggerEvent(coords)),
+        BotMove::Flag(coords) => mark_event_wr.send(TileMarkEvent(coords)),
+        BotMove::Chord(coords) => chord_event_wr.send(TileChordEvent(coords)),
+    }
+    board.assisted = true;
+    bot.last_move = Some(state);
+    bot.waited_ticks = 0;
+}
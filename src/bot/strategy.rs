@@ -0,0 +1,71 @@
+use crate::components::coordinates::Coordinates;
+use crate::solver::deduction::deduce;
+use crate::solver::{Probabilities, VisibleBoard, VisibleTile};
+
+/// An action of the bot, mirroring the board input events
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BotMove {
+    Reveal(Coordinates),
+    Flag(Coordinates),
+    Chord(Coordinates),
+}
+
+/// Every move the bot is sure about, or its best guess when nothing can be
+/// proven. Proven mines are flagged and numbers surrounded by proven flags
+/// are chorded
+pub fn next_moves(board: &VisibleBoard) -> Vec<BotMove> {
+    let deductions = deduce(board);
+    let mut mines = vec![false; board.len()];
+    for d in deductions.iter().filter(|d| d.mine) {
+        mines[d.cell] = true;
+    }
+    let mut moves: Vec<BotMove> = Vec::new();
+    let mut settled = vec![false; board.len()];
+
+    // Chords only rely on flags which are proven right, or exploded bombs
+    for i in 0..board.len() {
+        let number = match board.tile(i) {
+            VisibleTile::Revealed(v) if v > 0 => v as usize,
+            _ => continue,
+        };
+        let neighbors = board.neighbors(i);
+        let flags: Vec<usize> = neighbors
+            .iter()
+            .copied()
+            .filter(|n| matches!(board.tile(*n), VisibleTile::Flagged | VisibleTile::Mine))
+            .collect();
+        let covered: Vec<usize> = neighbors
+            .iter()
+            .copied()
+            .filter(|n| board.tile(*n) == VisibleTile::Covered && !settled[*n])
+            .collect();
+        if flags.len() == number
+            && !covered.is_empty()
+            && flags
+                .iter()
+                .all(|f| mines[*f] || board.tile(*f) == VisibleTile::Mine)
+        {
+            moves.push(BotMove::Chord(board.coords(i)));
+            for n in covered {
+                settled[n] = true;
+            }
+        }
+    }
+    for d in &deductions {
+        if settled[d.cell] {
+            continue;
+        }
+        match (d.mine, board.tile(d.cell)) {
+            (false, VisibleTile::Covered) => moves.push(BotMove::Reveal(board.coords(d.cell))),
+            (true, VisibleTile::Covered) => moves.push(BotMove::Flag(board.coords(d.cell))),
+            _ => continue,
+        }
+        settled[d.cell] = true;
+    }
+    if moves.is_empty() {
+        if let Some((cell, _)) = Probabilities::compute(board).safest(board) {
+            moves.push(BotMove::Reveal(board.coords(cell)));
+        }
+    }
+    moves
+}
@@ -7,6 +7,15 @@ pub struct Bounds2 {
 }
 
 impl Bounds2 {
+    /// Turns a cursor position, whose origin is the top left corner of the
+    /// window, into world space
+    pub fn cursor_to_world(window_size: Vec2, cursor: Vec2) -> Vec2 {
+        Vec2 {
+            x: cursor.x - window_size.x / 2.,
+            y: window_size.y / 2. - cursor.y,
+        }
+    }
+
     // Check whether the position is within the bounds
     pub fn is_bounds(&self, coor: Vec2) -> bool {
         coor.x >= self.position.x
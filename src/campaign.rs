@@ -0,0 +1,190 @@
+//! Campaign: a list of levels played in order, each one unlocking the next
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use bevy::asset::FileAssetIo;
+use bevy::log;
+use bevy::prelude::Resource;
+use serde::{Deserialize, Serialize};
+
+use crate::resources::board_options::BoardOptions;
+use crate::stats::{read_ron, write_ron};
+
+/// Campaign levels, relative to the asset folder
+pub const CAMPAIGN_ASSET: &str = "campaign.ron";
+
+/// A level of the campaign
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CampaignLevel {
+    pub title: String,
+    /// Time to beat, in seconds
+    pub par: u64,
+    /// Board of the level, either seeded or hand-authored with a `layout` code
+    pub options: BoardOptions,
+}
+
+impl CampaignLevel {
+    pub fn par(&self) -> Duration {
+        Duration::from_secs(self.par)
+    }
+}
+
+/// Levels of the campaign, in the order they are unlocked
+#[derive(Debug, Clone, Default, Resource)]
+pub struct Campaign {
+    pub levels: Vec<CampaignLevel>,
+}
+
+impl Campaign {
+    /// Loads the levels from the asset folder, the campaign being empty when
+    /// the file is missing or invalid
+    pub fn load_asset() -> Self {
+        let path = FileAssetIo::get_base_path()
+            .join("assets")
+            .join(CAMPAIGN_ASSET);
+        match Self::load(&path) {
+            Ok(campaign) => campaign,
+            Err(e) => {
+                log::error!("Failed to load the campaign from {:?}: {}", path, e);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Ok(Self {
+            levels: read_ron(path)?,
+        })
+    }
+}
+
+/// Resource present while a campaign level is played, the index of the level
+#[derive(Debug, Clone, Copy, Resource)]
+pub struct ActiveLevel(pub usize);
+
+/// Best time of every completed level, persisted as RON next to the statistics
+#[derive(Debug, Resource)]
+pub struct CampaignProgress {
+    pub path: PathBuf,
+    /// Best times by level title, so the levels can be reordered
+    pub best_times: HashMap<String, Duration>,
+}
+
+impl Default for CampaignProgress {
+    fn default() -> Self {
+        Self::load(Self::default_path())
+    }
+}
+
+impl CampaignProgress {
+    /// `campaign.ron` in the platform data directory
+    pub fn default_path() -> PathBuf {
+        dirs::data_dir()
+            .unwrap_or_default()
+            .join("mine")
+            .join("campaign.ron")
+    }
+
+    /// Loads the progress, starting from scratch when the file is missing or invalid
+    pub fn load(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let best_times = match read_ron(&path) {
+            Ok(best_times) => best_times,
+            Err(e) => {
+                if e.kind() != io::ErrorKind::NotFound {
+                    log::warn!("Failed to load campaign progress from {:?}: {}", path, e);
+                }
+                HashMap::new()
+            }
+        };
+        Self { path, best_times }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        write_ron(&self.path, &self.best_times)
+    }
+
+    pub fn best_time(&self, level: &CampaignLevel) -> Option<Duration> {
+        self.best_times.get(&level.title).copied()
+    }
+
+    /// The first level is always unlocked, the others once the previous one
+    /// is completed
+    pub fn is_unlocked(&self, campaign: &Campaign, index: usize) -> bool {
+        match index.checked_sub(1) {
+            None => true,
+            Some(previous) => campaign
+                .levels
+                .get(previous)
+                .is_some_and(|level| self.best_time(level).is_some()),
+        }
+    }
+
+    /// Records a completed level, keeping its best time, and saves the
+    /// progress. Returns whether the time is a new best
+    pub fn record(&mut self, level: &CampaignLevel, time: Duration) -> bool {
+        if self.best_time(level).is_some_and(|best| best <= time) {
+            return false;
+        }
+        self.best_times.insert(level.title.clone(), time);
+        if let Err(e) = self.save() {
+            log::error!("Failed to save campaign progress to {:?}: {}", self.path, e);
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bot::HeadlessGame;
+    use crate::resources::map::Map;
+
+    #[test]
+    fn asset_levels_are_solvable_without_guessing() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("assets")
+            .join(CAMPAIGN_ASSET);
+        let campaign = Campaign::load(&path).unwrap();
+        assert!(!campaign.levels.is_empty());
+        for level in &campaign.levels {
+            let mut game = match &level.options.layout {
+                Some(layout) => {
+                    let (map, start) = Map::decode(layout).unwrap();
+                    HeadlessGame::from_map(map, start)
+                }
+                None => HeadlessGame::new(&level.options, level.options.seed.unwrap()),
+            };
+            assert!(game.play_without_guessing(), "{}", level.title);
+        }
+    }
+
+    #[test]
+    fn completing_a_level_unlocks_the_next() {
+        let level = |title: &str| CampaignLevel {
+            title: title.to_string(),
+            par: 30,
+            options: Default::default(),
+        };
+        let campaign = Campaign {
+            levels: vec![level("first"), level("second"), level("third")],
+        };
+        let path = std::env::temp_dir()
+            .join(format!("mine-campaign-{}", std::process::id()))
+            .join("campaign.ron");
+        let mut progress = CampaignProgress::load(&path);
+        assert!(progress.is_unlocked(&campaign, 0));
+        assert!(!progress.is_unlocked(&campaign, 1));
+
+        assert!(progress.record(&campaign.levels[0], Duration::from_secs(20)));
+        assert!(!progress.record(&campaign.levels[0], Duration::from_secs(25)));
+        assert!(progress.is_unlocked(&campaign, 1));
+        assert!(!progress.is_unlocked(&campaign, 2));
+
+        let loaded = CampaignProgress::load(&path);
+        assert_eq!(loaded.best_times, progress.best_times);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+}
@@ -1,20 +1,66 @@
-use std::ops::Add;
-
 use bevy::prelude::Component;
 
-// location
-#[derive(Clone, Copy, Component, Debug, PartialEq, Eq, Hash)]
+/// Tile location on the board, `(0, 0)` being the bottom left tile
+#[derive(Clone, Copy, Component, Debug, Default, PartialEq, Eq, Hash)]
 pub struct Coordinates {
     pub x: u16,
     pub y: u16,
 }
 
-impl Add<(i8, i8)> for Coordinates {
-    type Output = Self;
+impl Coordinates {
+    pub const fn new(x: u16, y: u16) -> Self {
+        Self { x, y }
+    }
 
-    fn add(self, (x, y): (i8, i8)) -> Self::Output {
-        let x = ((self.x as i16) + x as i16) as u16;
-        let y = ((self.y as i16) + y as i16) as u16;
+    /// Offsets the coordinates by a delta, returning `None` when the result
+    /// falls outside of the `u16` range
+    pub fn checked_add(self, (x, y): (i8, i8)) -> Option<Self> {
+        Some(Self {
+            x: self.x.checked_add_signed(x.into())?,
+            y: self.y.checked_add_signed(y.into())?,
+        })
+    }
+}
+
+impl From<(u16, u16)> for Coordinates {
+    fn from((x, y): (u16, u16)) -> Self {
         Self { x, y }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checked_add_in_range() {
+        let coor = Coordinates { x: 3, y: 4 };
+        assert_eq!(coor.checked_add((-1, 1)), Some(Coordinates { x: 2, y: 5 }));
+        assert_eq!(coor.checked_add((0, 0)), Some(coor));
+    }
+
+    #[test]
+    fn checked_add_underflow() {
+        let coor = Coordinates { x: 0, y: 0 };
+        assert_eq!(coor.checked_add((-1, 0)), None);
+        assert_eq!(coor.checked_add((0, -1)), None);
+        assert_eq!(coor.checked_add((1, 1)), Some(Coordinates { x: 1, y: 1 }));
+    }
+
+    #[test]
+    fn checked_add_overflow() {
+        let coor = Coordinates {
+            x: u16::MAX,
+            y: u16::MAX,
+        };
+        assert_eq!(coor.checked_add((1, 0)), None);
+        assert_eq!(coor.checked_add((0, 1)), None);
+        assert_eq!(
+            coor.checked_add((-1, -1)),
+            Some(Coordinates {
+                x: u16::MAX - 1,
+                y: u16::MAX - 1
+            })
+        );
+    }
+}
@@ -0,0 +1,19 @@
+use bevy::prelude::Component;
+
+use crate::components::coordinates::Coordinates;
+use crate::resources::board::TileMark;
+
+/// Copy of an edge tile drawn across the opposite edge of a wrapping board
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Ghost {
+    pub source: Coordinates,
+}
+
+/// Part of a ghost following the state of its source tile
+#[derive(Component, Debug, Clone, Copy)]
+pub enum GhostLayer {
+    /// Shown while the source is covered
+    Cover,
+    /// Shown while the source carries the mark, whatever the mines of a flag
+    Mark(TileMark),
+}
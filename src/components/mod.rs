@@ -1,4 +1,5 @@
-pub(crate) mod coordinates;
+pub mod coordinates;
 pub mod bomb;
 pub mod bomb_neighber;
+pub mod ghost;
 pub mod uncover;
\ No newline at end of file
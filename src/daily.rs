@@ -0,0 +1,188 @@
+//! Daily challenge: one board per day and difficulty, the same for everyone
+use std::io;
+use std::path::PathBuf;
+
+use bevy::log;
+use bevy::prelude::Resource;
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::resources::difficulty::Difficulty;
+use crate::stats::{read_ron, write_ron, GameMetrics};
+
+/// Date of today's challenge, in UTC so every time zone plays the same board
+pub fn today() -> NaiveDate {
+    Utc::now().date_naive()
+}
+
+/// Board seed of a daily challenge, stable across runs and platforms
+pub fn daily_seed(date: NaiveDate, difficulty: Difficulty) -> u64 {
+    // FNV-1a, unlike `DefaultHasher` its output is not allowed to change
+    format!("{}-{}", date, difficulty.label())
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
+/// Resource present while a daily challenge board is played
+#[derive(Debug, Clone, Copy, Resource)]
+pub struct DailyChallenge {
+    pub date: NaiveDate,
+    pub difficulty: Difficulty,
+}
+
+/// Completed daily challenge
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DailyResult {
+    pub date: NaiveDate,
+    pub difficulty: Difficulty,
+    pub metrics: GameMetrics,
+}
+
+impl DailyResult {
+    /// One line summary to paste in a chat
+    pub fn share_text(&self) -> String {
+        format!(
+            "Mine daily {} {}: {:.2}s, 3BV/s {:.2}",
+            self.date,
+            self.difficulty.label(),
+            self.metrics.time.as_secs_f32(),
+            self.metrics.three_bv_per_second()
+        )
+    }
+}
+
+/// Daily results, persisted as RON next to the statistics
+#[derive(Debug, Resource)]
+pub struct DailyStore {
+    pub path: PathBuf,
+    /// Best result of each day and difficulty, oldest first
+    pub results: Vec<DailyResult>,
+}
+
+impl Default for DailyStore {
+    fn default() -> Self {
+        Self::load(Self::default_path())
+    }
+}
+
+impl DailyStore {
+    /// `daily.ron` in the platform data directory
+    pub fn default_path() -> PathBuf {
+        dirs::data_dir()
+            .unwrap_or_default()
+            .join("mine")
+            .join("daily.ron")
+    }
+
+    /// Loads the results, starting from scratch when the file is missing or invalid
+    pub fn load(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let results = match read_ron(&path) {
+            Ok(results) => results,
+            Err(e) => {
+                if e.kind() != io::ErrorKind::NotFound {
+                    log::warn!("Failed to load daily results from {:?}: {}", path, e);
+                }
+                Vec::new()
+            }
+        };
+        Self { path, results }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        write_ron(&self.path, &self.results)
+    }
+
+    pub fn get(&self, date: NaiveDate, difficulty: Difficulty) -> Option<&DailyResult> {
+        self.results
+            .iter()
+            .find(|r| r.date == date && r.difficulty == difficulty)
+    }
+
+    /// Records a completed challenge, keeping the fastest of a day, and
+    /// saves the results. Returns the kept result
+    pub fn record(&mut self, result: DailyResult) -> DailyResult {
+        let existing = self
+            .results
+            .iter_mut()
+            .find(|r| r.date == result.date && r.difficulty == result.difficulty);
+        let kept = match existing {
+            Some(r) if r.metrics.time <= result.metrics.time => *r,
+            Some(r) => {
+                *r = result;
+                result
+            }
+            None => {
+                self.results.push(result);
+                result
+            }
+        };
+        if let Err(e) = self.save() {
+            log::error!("Failed to save daily results to {:?}: {}", self.path, e);
+        }
+        kept
+    }
+
+    /// Every result as share lines, oldest first
+    pub fn export(&self) -> String {
+        self.results
+            .iter()
+            .map(DailyResult::share_text)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn result(day: u32, secs: u64) -> DailyResult {
+        DailyResult {
+            date: NaiveDate::from_ymd_opt(2023, 9, day).unwrap(),
+            difficulty: Difficulty::Beginner,
+            metrics: GameMetrics {
+                time: Duration::from_secs(secs),
+                three_bv: 20,
+                clicks: 25,
+            },
+        }
+    }
+
+    #[test]
+    fn seeds_depend_on_date_and_difficulty() {
+        let date = NaiveDate::from_ymd_opt(2023, 9, 1).unwrap();
+        let seed = daily_seed(date, Difficulty::Expert);
+        assert_eq!(seed, daily_seed(date, Difficulty::Expert));
+        assert_ne!(seed, daily_seed(date, Difficulty::Beginner));
+        assert_ne!(
+            seed,
+            daily_seed(date.succ_opt().unwrap(), Difficulty::Expert)
+        );
+    }
+
+    #[test]
+    fn keeps_the_best_result_of_a_day() {
+        let path = std::env::temp_dir()
+            .join(format!("mine-daily-{}", std::process::id()))
+            .join("daily.ron");
+        let mut store = DailyStore::load(&path);
+        store.record(result(1, 20));
+        assert_eq!(store.record(result(1, 30)), result(1, 20));
+        assert_eq!(store.record(result(1, 10)), result(1, 10));
+        store.record(result(2, 40));
+        assert_eq!(
+            store.export(),
+            "Mine daily 2023-09-01 Beginner: 10.00s, 3BV/s 2.00\n\
+             Mine daily 2023-09-02 Beginner: 40.00s, 3BV/s 0.50"
+        );
+
+        let loaded = DailyStore::load(&path);
+        assert_eq!(loaded.results, store.results);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+}
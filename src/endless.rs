@@ -0,0 +1,262 @@
+//! Endless mode: a board without edges, generated chunk by chunk from a seed
+//! as the player explores it
+use bevy::math::IVec2;
+use bevy::prelude::Resource;
+use bevy::utils::{HashMap, HashSet};
+
+use crate::components::coordinates::Coordinates;
+use crate::resources::map::Map;
+use crate::resources::tile::Tile;
+use crate::resources::topology::Neighborhood;
+
+/// Width and height of a chunk, in tiles
+pub const CHUNK_SIZE: i32 = 16;
+/// Mines of a chunk, the density of the intermediate board
+pub const CHUNK_MINES: u16 = 40;
+
+/// Positions of the 8 neighbors of a tile
+pub fn neighbors(position: IVec2) -> impl Iterator<Item = IVec2> {
+    Neighborhood::Moore
+        .deltas()
+        .iter()
+        .map(move |(x, y)| position + IVec2::new(*x as i32, *y as i32))
+}
+
+/// Chunk holding a tile
+pub fn chunk_of(position: IVec2) -> IVec2 {
+    IVec2::new(
+        position.x.div_euclid(CHUNK_SIZE),
+        position.y.div_euclid(CHUNK_SIZE),
+    )
+}
+
+/// Bombs of the endless board, each chunk being generated from the seed the
+/// first time it is read. The start tile and its neighbors never hold a bomb
+#[derive(Debug, Clone)]
+pub struct ChunkedMap {
+    seed: u64,
+    chunks: HashMap<IVec2, Map>,
+}
+
+impl ChunkedMap {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            chunks: HashMap::default(),
+        }
+    }
+
+    /// Number of chunks generated so far
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+
+    pub fn is_bomb_at(&mut self, position: IVec2) -> bool {
+        let chunk = chunk_of(position);
+        let local = position - chunk * CHUNK_SIZE;
+        self.chunk(chunk)
+            .is_bomb_at(Coordinates::new(local.x as u16, local.y as u16))
+    }
+
+    /// Hidden tile at the given position, its number counting the bombs of
+    /// the neighboring chunks too
+    pub fn tile(&mut self, position: IVec2) -> Tile {
+        if self.is_bomb_at(position) {
+            return Tile::Bomb(1);
+        }
+        match neighbors(position).filter(|p| self.is_bomb_at(*p)).count() {
+            0 => Tile::Empty,
+            count => Tile::BombNeighbor(count as u8),
+        }
+    }
+
+    fn chunk(&mut self, chunk: IVec2) -> &Map {
+        let seed = self.seed;
+        self.chunks
+            .entry(chunk)
+            .or_insert_with(|| generate_chunk(seed, chunk))
+    }
+}
+
+/// Endless game, the chunked counterpart of `Board`: every tile is covered
+/// until revealed and the game goes on until a bomb is hit
+#[derive(Debug, Resource)]
+pub struct EndlessGame {
+    pub map: ChunkedMap,
+    /// Uncovered tiles and what they show
+    revealed: HashMap<IVec2, Tile>,
+    flagged: HashSet<IVec2>,
+    /// Bomb which ended the game
+    exploded: Option<IVec2>,
+}
+
+impl EndlessGame {
+    /// Tile to uncover first, always an opening
+    pub const START: IVec2 = IVec2::ZERO;
+
+    pub fn new(seed: u64) -> Self {
+        Self {
+            map: ChunkedMap::new(seed),
+            revealed: HashMap::default(),
+            flagged: HashSet::default(),
+            exploded: None,
+        }
+    }
+
+    /// Tile shown at the given position, `None` while covered
+    pub fn revealed(&self, position: IVec2) -> Option<Tile> {
+        self.revealed.get(&position).copied()
+    }
+
+    pub fn is_flagged(&self, position: IVec2) -> bool {
+        self.flagged.contains(&position)
+    }
+
+    pub fn exploded(&self) -> Option<IVec2> {
+        self.exploded
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.exploded.is_some()
+    }
+
+    /// Number of tiles safely revealed
+    pub fn score(&self) -> usize {
+        self.revealed.len()
+    }
+
+    /// Uncovers a tile, flooding through the empty ones, and returns the
+    /// uncovered positions. Hitting a bomb ends the game
+    pub fn reveal(&mut self, position: IVec2) -> Vec<IVec2> {
+        if self.is_over() || self.is_flagged(position) || self.revealed.contains_key(&position) {
+            return Vec::new();
+        }
+        if self.map.is_bomb_at(position) {
+            self.exploded = Some(position);
+            return vec![position];
+        }
+        let mut uncovered = Vec::new();
+        let mut stack = vec![position];
+        while let Some(position) = stack.pop() {
+            if self.is_flagged(position) || self.revealed.contains_key(&position) {
+                continue;
+            }
+            let tile = self.map.tile(position);
+            self.revealed.insert(position, tile);
+            uncovered.push(position);
+            if tile == Tile::Empty {
+                stack.extend(neighbors(position));
+            }
+        }
+        uncovered
+    }
+
+    /// Reveals the neighbors of a number surrounded by as many flags
+    pub fn chord(&mut self, position: IVec2) -> Vec<IVec2> {
+        let Some(Tile::BombNeighbor(count)) = self.revealed(position) else {
+            return Vec::new();
+        };
+        let flags = neighbors(position).filter(|p| self.is_flagged(*p)).count();
+        if flags != count as usize {
+            return Vec::new();
+        }
+        neighbors(position).flat_map(|p| self.reveal(p)).collect()
+    }
+
+    /// Flags or unflags a covered tile, returns whether the tile changed
+    pub fn toggle_flag(&mut self, position: IVec2) -> bool {
+        if self.is_over() || self.revealed.contains_key(&position) {
+            return false;
+        }
+        if !self.flagged.remove(&position) {
+            self.flagged.insert(position);
+        }
+        true
+    }
+}
+
+fn generate_chunk(seed: u64, chunk: IVec2) -> Map {
+    let mut map = Map::empty(CHUNK_SIZE as u16, CHUNK_SIZE as u16);
+    map.set_bombs(CHUNK_MINES, chunk_seed(seed, chunk));
+    // The start tile opens the board
+    for position in neighbors(EndlessGame::START).chain([EndlessGame::START]) {
+        let local = position - chunk * CHUNK_SIZE;
+        if (0..CHUNK_SIZE).contains(&local.x) && (0..CHUNK_SIZE).contains(&local.y) {
+            map.set_mines_at(Coordinates::new(local.x as u16, local.y as u16), 0);
+        }
+    }
+    map
+}
+
+// FNV-1a of the board seed and the chunk position, stable across platforms
+fn chunk_seed(seed: u64, chunk: IVec2) -> u64 {
+    seed.to_le_bytes()
+        .into_iter()
+        .chain(chunk.x.to_le_bytes())
+        .chain(chunk.y.to_le_bytes())
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunks_are_generated_on_demand() {
+        let mut map = ChunkedMap::new(7);
+        assert_eq!(map.chunk_count(), 0);
+        // Numbers along a chunk edge count the bombs across it
+        let edge = IVec2::new(CHUNK_SIZE - 1, 5);
+        let bombs = neighbors(edge).filter(|p| map.is_bomb_at(*p)).count();
+        match map.tile(edge) {
+            Tile::BombNeighbor(count) => assert_eq!(count as usize, bombs),
+            Tile::Empty => assert_eq!(bombs, 0),
+            _ => (),
+        }
+        assert_eq!(map.chunk_count(), 2);
+        // The same seed gives the same bombs, far away chunks included
+        let mut other = ChunkedMap::new(7);
+        let far = IVec2::new(-63, 187) * CHUNK_SIZE;
+        assert!((0..64).all(|x| {
+            let p = far + IVec2::new(x, 0);
+            map.is_bomb_at(p) == other.is_bomb_at(p)
+        }));
+        let bombs = (0..CHUNK_SIZE)
+            .flat_map(|y| (0..CHUNK_SIZE).map(move |x| far + IVec2::new(x, y)))
+            .filter(|p| map.is_bomb_at(*p))
+            .count();
+        assert_eq!(bombs, CHUNK_MINES as usize);
+    }
+
+    #[test]
+    fn the_start_opens_the_board() {
+        let mut game = EndlessGame::new(3);
+        let opened = game.reveal(EndlessGame::START);
+        assert!(opened.len() >= 9);
+        assert_eq!(game.score(), opened.len());
+        assert_eq!(game.revealed(EndlessGame::START), Some(Tile::Empty));
+        // Revealing again changes nothing
+        assert!(game.reveal(EndlessGame::START).is_empty());
+    }
+
+    #[test]
+    fn a_bomb_ends_the_game() {
+        let mut game = EndlessGame::new(3);
+        game.reveal(EndlessGame::START);
+        let score = game.score();
+        let bomb = (5..100)
+            .map(|x| IVec2::new(x, -20))
+            .find(|p| game.map.is_bomb_at(*p))
+            .unwrap();
+        assert!(game.toggle_flag(bomb));
+        assert!(game.reveal(bomb).is_empty());
+        assert!(game.toggle_flag(bomb));
+        assert_eq!(game.reveal(bomb), vec![bomb]);
+        assert!(game.is_over());
+        assert_eq!(game.exploded(), Some(bomb));
+        assert_eq!(game.score(), score);
+        assert!(game.reveal(IVec2::new(1, 1)).is_empty());
+    }
+}
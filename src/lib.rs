@@ -1,48 +1,123 @@
+pub mod bot;
+pub mod campaign;
 pub mod components;
+pub mod daily;
+pub mod endless;
+pub mod menu;
+pub mod puzzle;
 pub mod resources;
+pub mod solver;
+pub mod stats;
 pub mod systems;
 pub mod bounds;
 
+pub use components::coordinates::Coordinates;
+pub use resources::board::Board;
+pub use resources::map::Map;
+pub use resources::tile::Tile;
+pub use systems::event::{
+    BombExploded, Chorded, GameLost, GameStarted, GameWon, HintRequestEvent, MoveUndone,
+    OverlayToggleEvent, TileChordEvent, TileFlagged, TileMarkEvent, TileRevealed,
+    TileTriggerEvent, TileUnflagged, UndoEvent,
+};
+
 use bevy::log;
 use bevy::prelude::*;
 use bevy::utils::HashMap;
 
 use resources::board_asset::*;
 use resources::board_options::*;
-use resources::map::*;
-use resources::tile::Tile;
 use resources::tile::Tile::*;
 
 use crate::bounds::Bounds2;
-use crate::components::coordinates::Coordinates;
+use crate::components::ghost::{Ghost, GhostLayer};
 use crate::components::uncover::Uncover;
-use crate::resources::board::Board;
-use crate::systems::event::TileTriggerEvent;
-use crate::systems::input::input_handler;
+use crate::resources::board::{GameStatus, TileMark};
+use crate::systems::ghost::update_ghosts;
+use crate::systems::hint::{clear_hint, show_hint};
+use crate::systems::input::{input_handler, keyboard_input_handler};
+use crate::systems::mark::mark_tiles;
+use crate::systems::overlay::{hover_overlay, update_overlay, ProbabilityOverlay};
+use crate::systems::timer::tick_timer;
+use crate::systems::uncover::chord_event_handler;
 use crate::systems::uncover::trigger_event_handler;
 use crate::systems::uncover::uncover_tiles;
+use crate::systems::undo::undo_move;
 
-pub struct BoardPlugin;
+/// Board plugin, the board only lives while the app is in `running_state`
+pub struct BoardPlugin<T> {
+    /// State in which the board is created, played and then cleaned up on exit
+    pub running_state: T,
+    /// States in which the board is kept but frozen, like a pause menu.
+    /// Coming back to `running_state` from one of them resumes the same board
+    pub paused_states: Vec<T>,
+}
 
-impl Plugin for BoardPlugin {
+impl<T: States> Plugin for BoardPlugin<T> {
     fn build(&self, app: &mut App) {
+        let running = || in_state(self.running_state.clone());
+        // The board is cleaned up once the app leaves all of these states
+        let mut board_states = self.paused_states.clone();
+        board_states.push(self.running_state.clone());
+        let leaving_board = move |state: Res<State<T>>| !board_states.contains(state.get());
+        for paused_state in &self.paused_states {
+            app.add_systems(
+                OnExit(paused_state.clone()),
+                Self::cleanup_board.run_if(leaving_board.clone()),
+            );
+        }
         app
-            .add_systems(Startup, Self::create_board) // 初始化主游戏界面
-            .add_systems(Update, input_handler) // 增加输入处理
-            .add_systems(Update, trigger_event_handler) // 怎么输出事件绑定对应的处理方式
-            .add_systems(Update, uncover_tiles) // 取消覆盖
-            .add_event::<TileTriggerEvent>();
+            .add_systems(
+                OnEnter(self.running_state.clone()),
+                Self::create_board.run_if(not(resource_exists::<Board>())),
+            ) // 初始化主游戏界面
+            .add_systems(Update, input_handler.run_if(running())) // 增加输入处理
+            .add_systems(Update, keyboard_input_handler.run_if(running())) // 键盘输入
+            .add_systems(Update, trigger_event_handler.run_if(running())) // 怎么输出事件绑定对应的处理方式
+            .add_systems(Update, chord_event_handler.run_if(running())) // 快速翻开周围的格子
+            .add_systems(Update, mark_tiles.run_if(running())) // 插旗
+            .add_systems(Update, uncover_tiles.run_if(running())) // 取消覆盖
+            .add_systems(Update, tick_timer.run_if(running())) // 计时
+            .add_systems(
+                Update,
+                (undo_move, apply_deferred, update_overlay, clear_hint, show_hint)
+                    .chain()
+                    .run_if(running()),
+            ) // 撤销、概率分析与提示
+            .add_systems(Update, hover_overlay.run_if(running())) // 悬停显示概率
+            .add_systems(Update, update_ghosts.run_if(running())) // 环绕棋盘边缘的镜像格子
+            .init_resource::<ProbabilityOverlay>()
+            .add_systems(
+                OnExit(self.running_state.clone()),
+                Self::cleanup_board.run_if(leaving_board),
+            ) // 清理游戏界面
+            .add_event::<TileTriggerEvent>()
+            .add_event::<TileMarkEvent>()
+            .add_event::<TileChordEvent>()
+            .add_event::<HintRequestEvent>()
+            .add_event::<OverlayToggleEvent>()
+            .add_event::<UndoEvent>()
+            .add_event::<TileRevealed>()
+            .add_event::<TileFlagged>()
+            .add_event::<TileUnflagged>()
+            .add_event::<Chorded>()
+            .add_event::<MoveUndone>()
+            .add_event::<BombExploded>()
+            .add_event::<GameStarted>()
+            .add_event::<GameWon>()
+            .add_event::<GameLost>();
 
         log::info!("Loaded Board Plugin");
     }
 }
 
-impl BoardPlugin {
+impl<T> BoardPlugin<T> {
     pub fn create_board(
         mut commands: Commands,
         board_options: Option<Res<BoardOptions>>,
         board_assert: Option<Res<BoardAsset>>,
-        window: Query<&Window>
+        window: Query<&Window>,
+        mut game_started_event: EventWriter<GameStarted>,
     ) {
         // 拿到初始化borad的参数
         let board_options = match board_options {
@@ -54,30 +129,49 @@ impl BoardPlugin {
             None => panic!("failed to find board assert"),
             Some(o) => o.clone(),
         };
-        // Initialize the map
-        let mut map = Map::empty(
-            board_options.map_size.0, 
-            board_options.map_size.1,
-        );
         // Set board bombs
-        map.set_bombs(board_options.boom_count);
+        let seed = board_options.seed.unwrap_or_else(rand::random);
+        let layout = board_options.layout.as_deref().and_then(|code| {
+            Map::decode(code)
+                .map_err(|e| log::error!("Invalid board code, playing a random board: {}", e))
+                .ok()
+        });
+        // Initialize the map, from the given layout if any
+        let (map, start) = match layout {
+            Some((map, start)) => (map, start),
+            None => {
+                let mut map = board_options.empty_map();
+                map.set_bombs(board_options.boom_count, seed);
+                // Safe Start, Select a tile to uncover which is empty
+                let start = board_options.safe_place.then(|| {
+                    map.iter()
+                        .find(|(_, tile)| **tile == Tile::Empty)
+                        .map(|(coordinates, _)| coordinates)
+                });
+                (map, start.flatten())
+            }
+        };
 
         #[cfg(feature = "debug")]
         log::info!("{}", map.console_output());
 
         // We define the size of the tiles in world space
+        let topology = map.topology();
+        // Wrapping edges are bordered by a row of ghost tiles
+        let (wrap_x, wrap_y) = map.wraps();
+        let fitted_size = (
+            map.width() + 2 * wrap_x as u16,
+            map.height() + 2 * wrap_y as u16,
+        );
         let tile_size = match board_options.tile_size {
             TileSize::Fixed(v) => v,
             TileSize::Adaptative { min, max } => Self::adaptative_tile_size(
                 window, 
                 (min, max), 
-                (map.width(), map.height())
+                topology.board_size(fitted_size, 1.),
             ),
         };
-        let board_size = Vec2::new(
-            map.width() as f32 * tile_size,
-            map.height() as f32 * tile_size,
-        );
+        let board_size = topology.board_size((map.width(), map.height()), tile_size);
         // We define the board anchor position 
         let board_position = match board_options.position {
             BoardPosition::Centered { offset } => {
@@ -90,8 +184,7 @@ impl BoardPlugin {
             BoardPosition::Custom(p) => p,
         };
         // Init assert
-        let mut covered_tiles = HashMap::with_capacity((map.width() * map.height()).into());
-        let mut safe_start = None;
+        let mut covered_tiles = HashMap::with_capacity(map.width() as usize * map.height() as usize);
 
         let board_entity = commands
             .spawn(SpriteBundle::default())
@@ -122,11 +215,18 @@ impl BoardPlugin {
                     board_options.tile_padding,
                     &board_assert,
                     &mut covered_tiles,
-                    &mut safe_start,
+                );
+                Self::spawn_ghosts(
+                    parent,
+                    &map,
+                    tile_size,
+                    board_options.tile_padding,
+                    &board_assert,
                 );
             })
             .id();
         
+        let start_entity = start.and_then(|coordinates| covered_tiles.get(&coordinates).copied());
         // Add the main board resource
         commands.insert_resource(Board {
             tile_map: map,
@@ -137,32 +237,49 @@ impl BoardPlugin {
                 },
                 size: board_size,
             },
-            tile_size: tile_size,
+            tile_size,
             covered_tiles,
-            entity: board_entity
+            flagged_tiles: HashMap::default(),
+            question_tiles: HashMap::default(),
+            exploded_tiles: Default::default(),
+            lives: board_options.lives.max(1),
+            entity: board_entity,
+            seed,
+            start,
+            status: GameStatus::Playing,
+            timer: Default::default(),
+            clicks: 0,
+            assisted: false,
+            history: Default::default(),
         });
+        game_started_event.send(GameStarted);
 
-        // Safe Start, Select a tile to uncover which is empty
-        if board_options.safe_place {
-            if let Some(entity) = safe_start {
-                commands.entity(entity).insert(Uncover);
-            }
+        if let Some(entity) = start_entity {
+            commands.entity(entity).insert(Uncover);
+        }
+    }
+
+    /// Despawns the board entity and removes the `Board` resource
+    pub fn cleanup_board(board: Option<Res<Board>>, mut commands: Commands) {
+        if let Some(board) = board {
+            board.despawn(&mut commands);
         }
     }
 
-    /// Computes a tile size that matches the window according to the tile map size
+    /// Computes a tile size that matches the window according to the board
+    /// size in tiles
     fn adaptative_tile_size(
         window: Query<&Window>,
         (min, max): (f32, f32),
-        (width, height): (u16, u16),
+        board_size: Vec2,
     ) -> f32 {
         let window = window.single();
-        let max_width = window.width() / width as f32;
-        let max_height = window.height() / height as f32;
+        let max_width = window.width() / board_size.x;
+        let max_height = window.height() / board_size.y;
         max_width.min(max_height).clamp(min, max)
     }
 
-    /// Spawn tile
+    /// Spawn tile, void tiles being left out
     fn spawn_tiles(
         parent: &mut ChildBuilder,
         map: &Map,
@@ -170,107 +287,142 @@ impl BoardPlugin {
         padding: f32,
         board_assert: &BoardAsset,
         covered_tiles: &mut HashMap<Coordinates, Entity>,
-        safe_start_entity: &mut Option<Entity>,
     ) {
+        let topology = map.topology();
         // Tiles
-        for (y, line) in map.iter().enumerate() {
-            for (x, tile) in line.iter().enumerate() {
-                let coordinates = Coordinates {
-                    x: x as u16,
-                    y: (map.len() -1 - y) as u16,
-                };
-                let mut cmd = parent.spawn_empty();
-                cmd.insert(SpriteBundle {
-                        sprite: Sprite {
-                            color: board_assert.tile_material.color,
-                            custom_size: Some(Vec2::splat(
-                                size - padding as f32,
-                            )),
-                            ..Default::default()
-                        },
-                        texture: board_assert.tile_material.texture.clone(),
-                        transform: Transform::from_xyz(
-                            ( x as f32 * size ) + ( size / 2. ), 
-                            ( y as f32 * size ) + ( size / 2. ), 
-                            1.,
-                        ),
+        for (coordinates, tile) in map.iter() {
+            if *tile == Void {
+                continue;
+            }
+            let (x, y) = (coordinates.x, coordinates.y);
+            let mut cmd = parent.spawn_empty();
+            cmd.insert(SpriteBundle {
+                    sprite: Sprite {
+                        color: board_assert.tile_material.color,
+                        custom_size: Some(topology.tile_sprite_size(size - padding)),
                         ..Default::default()
-                    })
-                    .insert(Name::new(format!("Tiles ({}, {})", x, y)))
-                    .with_children(|parent| {// Set the split Cover
-                    let entity = parent.spawn(
-                        SpriteBundle {
+                    },
+                    texture: board_assert.tile_texture(&board_assert.tile_material, topology),
+                    transform: Transform::from_translation(
+                        topology.tile_center(coordinates, size).extend(1.),
+                    ),
+                    ..Default::default()
+                })
+                .insert(Name::new(format!("Tiles ({}, {})", x, y)))
+                .insert(coordinates)
+                .with_children(|parent| {// Set the split Cover
+                let entity = parent.spawn(board_assert.cover_bundle(topology, size - padding))
+                    .insert(Name::new("Tile Cover"))
+                    .id();
+                covered_tiles.insert(coordinates, entity);
+            });
+
+            // Inset bomb sprites
+            match tile {
+                Bomb(mines) => {
+                    cmd.insert(components::bomb::Bomb);
+                    cmd.with_children(|parent| {
+                        parent
+                            .spawn(board_assert.bomb_bundle(size - padding))
+                            .with_children(|parent| {
+                                // Tile holding several mines
+                                if *mines > 1 {
+                                    parent.spawn(board_assert.mine_count_text_bundle(*mines, size - padding));
+                                }
+                            });
+                    });
+                },
+                BombNeighbor(v) => {
+                    cmd.insert(components::bomb_neighber::BombNeighbor {count: *v});
+                    cmd.with_children(|parent| {
+                        parent.spawn(board_assert.bomb_count_text_bundle(*v, size - padding));
+                    });
+                },
+                Empty | Void => (),
+            }
+        }
+    }
+
+    /// Spawns faded copies of the edge tiles across the opposite edges of a
+    /// wrapping board, so that wrapped numbers can be read
+    fn spawn_ghosts(
+        parent: &mut ChildBuilder,
+        map: &Map,
+        size: f32,
+        padding: f32,
+        board_asset: &BoardAsset,
+    ) {
+        let (wrap_x, wrap_y) = map.wraps();
+        if !wrap_x && !wrap_y {
+            return;
+        }
+        let topology = map.topology();
+        let period = topology.wrap_period((map.width(), map.height()), size);
+        // Copies of a tile: the ones of the last column go left of the board,
+        // those of the first column right of it, and so on
+        let copies = |value: u16, len: u16, wraps: bool| {
+            let mut res = vec![0.];
+            if wraps && value == len - 1 {
+                res.push(-1.);
+            }
+            if wraps && value == 0 {
+                res.push(1.);
+            }
+            res
+        };
+        for (coordinates, tile) in map.iter().filter(|(_, tile)| **tile != Void) {
+            for dx in copies(coordinates.x, map.width(), wrap_x) {
+                for dy in copies(coordinates.y, map.height(), wrap_y) {
+                    if dx == 0. && dy == 0. {
+                        continue;
+                    }
+                    let translation = topology.tile_center(coordinates, size)
+                        + Vec2::new(dx, dy) * period;
+                    let ghost = Ghost { source: coordinates };
+                    parent
+                        .spawn(SpriteBundle {
                             sprite: Sprite {
-                                custom_size: Some(Vec2::splat(size - padding)),
-                                color: board_assert.covered_tile_material.color,
+                                color: board_asset.tile_material.color.with_a(0.5),
+                                custom_size: Some(topology.tile_sprite_size(size - padding)),
                                 ..Default::default()
                             },
-                            transform: Transform::from_xyz(0., 0., 2.),
+                            texture: board_asset.tile_texture(&board_asset.tile_material, topology),
+                            transform: Transform::from_translation(translation.extend(1.)),
                             ..Default::default()
                         })
-                        .insert(Name::new("Tile Cover"))
-                        .id();
-                    covered_tiles.insert(coordinates, entity);
-                    // Safe Start
-                    if safe_start_entity.is_none() && *tile == Tile::Empty {
-                        *safe_start_entity = Some(entity);
-                    }
-                });
-
-                // Inset bomb sprites
-                match tile {
-                    Bomb => {
-                        cmd.insert(components::bomb::Bomb);
-                        cmd.with_children(|parent| {
-                            parent.spawn(SpriteBundle {
-                                sprite: Sprite {
-                                    color: Color::BLACK,
-                                    custom_size: Some(Vec2::splat(size - padding)),
-                                    ..Default::default()
+                        .insert(Name::new(format!("Ghost ({}, {})", coordinates.x, coordinates.y)))
+                        .with_children(|parent| {
+                            match tile {
+                                Bomb(mines) => {
+                                    parent
+                                        .spawn(board_asset.bomb_bundle(size - padding))
+                                        .with_children(|parent| {
+                                            if *mines > 1 {
+                                                parent.spawn(board_asset.mine_count_text_bundle(*mines, size - padding));
+                                            }
+                                        });
                                 },
-                                transform: Transform::from_xyz(0., 0., 1.),
-                                texture: board_assert.bomb_material.texture.clone(),
-                                ..Default::default()
-                            });
-                        });
-                    },
-                    BombNeighbor(v) => {
-                        cmd.insert(components::bomb_neighber::BombNeighbor {count: *v});
-                        cmd.with_children(|parent| {
-                            parent.spawn(Self::bomb_count_text_bundle(
-                                *v, 
-                                board_assert, 
-                                size - padding,
-                            ));
+                                BombNeighbor(v) => {
+                                    parent.spawn(board_asset.bomb_count_text_bundle(*v, size - padding));
+                                },
+                                Empty | Void => (),
+                            }
+                            // Darker but opaque, the number must stay hidden
+                            let mut cover = board_asset.cover_bundle(topology, size - padding);
+                            cover.sprite.color *= 0.7;
+                            parent
+                                .spawn(cover)
+                                .insert((ghost, GhostLayer::Cover))
+                                .with_children(|parent| {
+                                    for mark in [TileMark::Flag(1), TileMark::Question] {
+                                        parent
+                                            .spawn(board_asset.mark_bundle(mark, size - padding))
+                                            .insert((ghost, GhostLayer::Mark(mark), Visibility::Hidden));
+                                    }
+                                });
                         });
-                    },
-                    Empty => (),
                 }
             }
         }
-
-
     }
-
-    /// Generates the bomb counter txtx 2D Bundle for a given value
-    fn bomb_count_text_bundle(count: u8, board_assert: &BoardAsset, size: f32) -> Text2dBundle {
-        let color = board_assert.bomb_counter_color(count);
-        // General a text bundle
-        Text2dBundle {
-            text: Text { 
-                sections: vec![TextSection {
-                    value: count.to_string(),
-                    style: TextStyle { 
-                        font: board_assert.bomb_counter_font.clone(),
-                        font_size: size, 
-                        color 
-                    },
-                }],
-                alignment: TextAlignment::Center,
-                ..Default::default()
-            },
-            transform: Transform::from_xyz(0., 0., 1.),
-            ..Default::default()
-        }
-    }
-}
\ No newline at end of file
+}
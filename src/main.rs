@@ -2,8 +2,8 @@ use bevy::prelude::*;
 use bevy::window::{PresentMode, WindowTheme};
 
 use mine::BoardPlugin;
-use mine::resources::board_asset::{SpriteMaterial, BoardAsset};
-use mine::resources::board_options::BoardOptions;
+use mine::bot::BotPlugin;
+use mine::menu::{AppState, MenuPlugin};
 
 fn main() {
     // Init the world
@@ -24,49 +24,22 @@ fn main() {
         ..default()
     }));
 
-    // Init the board
+    // Init the menus and the board, whose options come from the settings screen
     app
-        .add_plugins(BoardPlugin)
+        .add_plugins(MenuPlugin)
+        .add_plugins(BoardPlugin {
+            running_state: AppState::Playing,
+            paused_states: vec![AppState::Paused, AppState::GameOver],
+        })
+        .add_plugins(BotPlugin {
+            running_state: AppState::Playing,
+        })
         .add_systems(Startup, setup)
         .run();
 }
 
 // Set Camera
-fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+fn setup(mut commands: Commands) {
     // 2D orthographic camera
     commands.spawn(Camera2dBundle::default());
-    // Board Option
-    commands.insert_resource(BoardOptions { // 初始化游戏资源
-        map_size: (20, 20),
-        boom_count: 40,
-        tile_padding: 3.0,
-        safe_place: true,
-        ..Default::default()
-    });
-    // Board Asset
-    commands.insert_resource(BoardAsset {
-        label: "Default".to_string(),
-        board_material: SpriteMaterial {
-            color: Color::WHITE,
-            ..Default::default()
-        },
-        tile_material: SpriteMaterial {
-            color: Color::DARK_GRAY,
-            ..Default::default()
-        },
-        covered_tile_material: SpriteMaterial { 
-            color: Color::GRAY, 
-            ..Default::default()
-        },
-        bomb_counter_font: asset_server.load("fonts/pixeled.ttf"),
-        bomb_counter_colors: BoardAsset::default_color(),
-        flag_material: SpriteMaterial {
-            color: Color::WHITE,
-            texture: asset_server.load("sprites/flag.png")
-        },
-        bomb_material: SpriteMaterial {
-            color: Color::WHITE,
-            texture: asset_server.load("sprites/bomb.png")
-        },
-    });
-}
\ No newline at end of file
+}
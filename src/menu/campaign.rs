@@ -0,0 +1,47 @@
+use bevy::prelude::*;
+
+use crate::campaign::{Campaign, CampaignProgress};
+
+use super::widgets::{row, screen_root, spawn_button, text};
+use super::{MenuAction, MenuFont};
+
+/// Marker of the level select screen entities
+#[derive(Component)]
+pub struct CampaignScreen;
+
+pub fn spawn(
+    mut commands: Commands,
+    font: Res<MenuFont>,
+    campaign: Res<Campaign>,
+    progress: Res<CampaignProgress>,
+) {
+    let font = &font.0;
+    commands
+        .spawn(screen_root(Color::BLACK))
+        .insert((CampaignScreen, Name::new("Campaign")))
+        .with_children(|parent| {
+            parent.spawn(text(font, "Campaign", 40.));
+            if campaign.levels.is_empty() {
+                parent.spawn(text(font, "No level found", 20.));
+            }
+            for (i, level) in campaign.levels.iter().enumerate() {
+                let label = format!("{}. {}", i + 1, level.title);
+                parent.spawn(row()).with_children(|parent| {
+                    if !progress.is_unlocked(&campaign, i) {
+                        parent.spawn(text(font, format!("{} (locked)", label), 16.));
+                        return;
+                    }
+                    spawn_button(parent, font, label, MenuAction::PlayLevel(i));
+                    let best = match progress.best_time(level) {
+                        Some(best) if best <= level.par() => {
+                            format!("best {:.1}s, under par", best.as_secs_f32())
+                        }
+                        Some(best) => format!("best {:.1}s", best.as_secs_f32()),
+                        None => "not completed".to_string(),
+                    };
+                    parent.spawn(text(font, format!("par {}s, {}", level.par, best), 14.));
+                });
+            }
+            spawn_button(parent, font, "Back", MenuAction::MainMenu);
+        });
+}
@@ -0,0 +1,18 @@
+use arboard::Clipboard;
+use bevy::log;
+
+/// Puts the text in the system clipboard, logging it when not available
+pub fn copy(text: &str) {
+    match Clipboard::new().and_then(|mut c| c.set_text(text)) {
+        Ok(()) => log::info!("Copied {}", text),
+        Err(e) => log::warn!("Failed to copy {} to the clipboard: {}", text, e),
+    }
+}
+
+/// Text of the system clipboard
+pub fn paste() -> Option<String> {
+    Clipboard::new()
+        .and_then(|mut c| c.get_text())
+        .map_err(|e| log::warn!("Failed to read the clipboard: {}", e))
+        .ok()
+}
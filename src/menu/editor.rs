@@ -0,0 +1,387 @@
+use bevy::input::{mouse::MouseButtonInput, ButtonState};
+use bevy::log;
+use bevy::prelude::*;
+use chrono::Local;
+
+use crate::bot::HeadlessGame;
+use crate::bounds::Bounds2;
+use crate::campaign::ActiveLevel;
+use crate::components::coordinates::Coordinates;
+use crate::daily::DailyChallenge;
+use crate::puzzle::Puzzle;
+use crate::resources::board::Board;
+use crate::resources::board_asset::BoardAsset;
+use crate::resources::board_options::BoardOptions;
+use crate::resources::map::Map;
+use crate::resources::tile::Tile;
+use crate::solver;
+
+use super::widgets::{row, spawn_button, text};
+use super::{AppState, MenuAction, MenuFont, Settings};
+
+// Window height kept for the editor panel above the board
+const PANEL_HEIGHT: f32 = 280.;
+
+/// Board being authored
+#[derive(Debug, Resource)]
+pub struct Editor {
+    pub map: Map,
+    /// Tile uncovered when the puzzle starts
+    pub start: Option<Coordinates>,
+    /// Outcome of the last edit, check or save
+    pub message: String,
+}
+
+impl Editor {
+    /// Starts editing a board without bombs, of the size and rules of `map`
+    pub fn new(map: &Map) -> Self {
+        Self {
+            map: map.without_bombs(),
+            start: None,
+            message: "Left click: mines, right click: start tile".to_string(),
+        }
+    }
+
+    /// Adds a mine, or clears the tile once it holds as many as it can, the
+    /// numbers being recomputed
+    pub fn cycle_mines(&mut self, coords: Coordinates) {
+        let mines = (self.map.mines_at(coords) + 1) % (self.map.max_mines() + 1);
+        self.map.set_mines_at(coords, mines);
+        if self.start == Some(coords) {
+            self.start = None;
+        }
+        self.message = format!("{} bombs", self.map.bomb_count());
+    }
+
+    /// Picks the start tile, or unsets it when picked again
+    pub fn toggle_start(&mut self, coords: Coordinates) {
+        if self.map.is_bomb_at(coords) {
+            self.message = "The start tile must be safe".to_string();
+        } else if self.start == Some(coords) {
+            self.start = None;
+        } else {
+            self.start = Some(coords);
+        }
+    }
+}
+
+/// What an editor button does when pressed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditorAction {
+    /// Plays the puzzle with deductions only
+    Check,
+    Save,
+    Play,
+    Clear,
+}
+
+/// Where the editor board is drawn
+#[derive(Debug, Resource)]
+pub struct EditorView {
+    pub bounds: Bounds2,
+    pub tile_size: f32,
+}
+
+/// Marker of the editor panel entities
+#[derive(Component)]
+pub struct EditorScreen;
+
+/// Marker of the editor board entity
+#[derive(Component)]
+pub struct EditorBoard;
+
+#[derive(Component)]
+pub struct EditorMessage;
+
+/// Loads the board assets and opens an empty board from the settings. A
+/// board being edited is kept once it holds a bomb, an editor without any
+/// being rebuilt from the current settings, its start tile included
+pub fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<Settings>,
+    editor: Option<Res<Editor>>,
+) {
+    commands.insert_resource(settings.theme.board_asset(&asset_server));
+    if editor.is_none_or(|e| e.map.bomb_count() == 0) {
+        let map = settings.board_options().empty_map();
+        commands.insert_resource(Editor::new(&map));
+    }
+}
+
+pub fn spawn(mut commands: Commands, font: Res<MenuFont>) {
+    let font = &font.0;
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Percent(100.),
+                height: Val::Px(PANEL_HEIGHT),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(10.),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .insert((EditorScreen, Name::new("Editor")))
+        .with_children(|parent| {
+            parent.spawn(text(font, "Editor", 30.));
+            parent.spawn((text(font, "", 12.), EditorMessage));
+            parent.spawn(row()).with_children(|parent| {
+                let check = MenuAction::Editor(EditorAction::Check);
+                spawn_button(parent, font, "Check solvability", check);
+                spawn_button(parent, font, "Save", MenuAction::Editor(EditorAction::Save));
+            });
+            parent.spawn(row()).with_children(|parent| {
+                spawn_button(parent, font, "Play", MenuAction::Editor(EditorAction::Play));
+                spawn_button(
+                    parent,
+                    font,
+                    "Clear",
+                    MenuAction::Editor(EditorAction::Clear),
+                );
+                spawn_button(parent, font, "Back", MenuAction::MainMenu);
+            });
+        });
+}
+
+/// Redraws the board whenever the puzzle changes
+pub fn render(
+    mut commands: Commands,
+    editor: Res<Editor>,
+    board_asset: Res<BoardAsset>,
+    window: Query<&Window>,
+    boards: Query<Entity, With<EditorBoard>>,
+) {
+    if !editor.is_changed() && !boards.is_empty() {
+        return;
+    }
+    for entity in boards.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    let window = window.single();
+    let topology = editor.map.topology();
+    let map_size = (editor.map.width(), editor.map.height());
+    let unit_size = topology.board_size(map_size, 1.);
+    let tile_size = ((window.width() - 20.) / unit_size.x)
+        .min((window.height() - PANEL_HEIGHT - 20.) / unit_size.y)
+        .clamp(10., 50.);
+    let padding = 3.;
+    let size = unit_size * tile_size;
+    // Centered in the window area below the panel
+    let position = Vec2::new(-size.x / 2., -PANEL_HEIGHT / 2. - size.y / 2.);
+
+    commands
+        .spawn(SpriteBundle::default())
+        .insert((EditorBoard, Name::new("Editor Board")))
+        .insert(Transform::from_translation(position.extend(0.)))
+        .with_children(|parent| {
+            parent.spawn(SpriteBundle {
+                sprite: Sprite {
+                    color: board_asset.board_material.color,
+                    custom_size: Some(size),
+                    ..Default::default()
+                },
+                transform: Transform::from_xyz(size.x / 2., size.y / 2., 0.),
+                ..Default::default()
+            });
+            for (coords, tile) in editor.map.iter().filter(|(_, t)| **t != Tile::Void) {
+                let color = if editor.start == Some(coords) {
+                    Color::GREEN
+                } else {
+                    board_asset.tile_material.color
+                };
+                let translation = topology.tile_center(coords, tile_size);
+                parent
+                    .spawn(SpriteBundle {
+                        sprite: Sprite {
+                            color,
+                            custom_size: Some(topology.tile_sprite_size(tile_size - padding)),
+                            ..Default::default()
+                        },
+                        texture: board_asset.tile_texture(&board_asset.tile_material, topology),
+                        transform: Transform::from_translation(translation.extend(1.)),
+                        ..Default::default()
+                    })
+                    .with_children(|parent| match tile {
+                        Tile::Bomb(mines) => {
+                            parent
+                                .spawn(board_asset.bomb_bundle(tile_size - padding))
+                                .with_children(|parent| {
+                                    if *mines > 1 {
+                                        let size = tile_size - padding;
+                                        parent.spawn(
+                                            board_asset.mine_count_text_bundle(*mines, size),
+                                        );
+                                    }
+                                });
+                        }
+                        Tile::BombNeighbor(v) => {
+                            parent
+                                .spawn(board_asset.bomb_count_text_bundle(*v, tile_size - padding));
+                        }
+                        Tile::Empty | Tile::Void => (),
+                    });
+            }
+        });
+    commands.insert_resource(EditorView {
+        bounds: Bounds2 { position, size },
+        tile_size,
+    });
+}
+
+/// Left clicks add mines, right clicks pick the start tile
+pub fn input(
+    window: Query<&Window>,
+    view: Option<Res<EditorView>>,
+    buttons: Query<&Interaction, With<Button>>,
+    mut editor: ResMut<Editor>,
+    mut button_evr: EventReader<MouseButtonInput>,
+) {
+    let window = window.single();
+    let Some(view) = view else {
+        return;
+    };
+    // Clicks on the panel are for its buttons
+    let on_button = buttons.iter().any(|i| *i != Interaction::None);
+    for event in button_evr.iter() {
+        if event.state != ButtonState::Pressed || on_button {
+            continue;
+        }
+        let Some(cursor) = window.cursor_position() else {
+            continue;
+        };
+        let window_size = Vec2::new(window.width(), window.height());
+        let position = Bounds2::cursor_to_world(window_size, cursor);
+        if !view.bounds.is_bounds(position) {
+            continue;
+        }
+        let topology = editor.map.topology();
+        let local = position - view.bounds.position;
+        let Some(coords) = topology
+            .tile_at(local, view.tile_size)
+            .filter(|c| editor.map.contains(*c) && !editor.map.is_void_at(*c))
+        else {
+            continue;
+        };
+        match event.button {
+            MouseButton::Left => editor.cycle_mines(coords),
+            MouseButton::Right => editor.toggle_start(coords),
+            _ => (),
+        }
+    }
+}
+
+pub fn update_message(editor: Res<Editor>, mut texts: Query<&mut Text, With<EditorMessage>>) {
+    for mut text in texts.iter_mut() {
+        if editor.is_changed() || text.is_added() {
+            text.sections[0].value = editor.message.clone();
+        }
+    }
+}
+
+pub fn editor_action(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<Settings>,
+    board: Option<Res<Board>>,
+    mut editor: ResMut<Editor>,
+    buttons: Query<(&Interaction, &MenuAction), Changed<Interaction>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    for (interaction, action) in buttons.iter() {
+        let MenuAction::Editor(action) = action else {
+            continue;
+        };
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        if *action == EditorAction::Clear {
+            *editor = Editor::new(&editor.map);
+            continue;
+        }
+        let Some(start) = editor.start else {
+            editor.message = "Pick a start tile with a right click first".to_string();
+            continue;
+        };
+        match action {
+            EditorAction::Check if !solver::supports(&editor.map) => {
+                editor.message = "The check only handles a mine per tile".to_string();
+            }
+            EditorAction::Check => {
+                let mut game = HeadlessGame::from_map(editor.map.clone(), Some(start));
+                editor.message = if game.play_without_guessing() {
+                    "Solvable without guessing".to_string()
+                } else {
+                    format!(
+                        "A guess is needed with {} safe tiles left",
+                        game.covered_safe_tiles()
+                    )
+                };
+            }
+            EditorAction::Save => {
+                let name = format!("puzzle-{}", Local::now().format("%Y%m%d-%H%M%S"));
+                let path = Puzzle::default_dir().join(format!("{}.ron", name));
+                editor.message = match Puzzle::new(name, &editor.map, start).save(&path) {
+                    Ok(()) => format!("Saved to {}", path.display()),
+                    Err(e) => {
+                        log::error!("Failed to save the puzzle to {:?}: {}", path, e);
+                        format!("Failed to save: {}", e)
+                    }
+                };
+            }
+            EditorAction::Play => {
+                if let Some(board) = &board {
+                    board.despawn(&mut commands);
+                }
+                commands.insert_resource(BoardOptions {
+                    layout: Some(editor.map.encode(Some(start))),
+                    ..settings.board_options()
+                });
+                commands.remove_resource::<DailyChallenge>();
+                commands.remove_resource::<ActiveLevel>();
+                commands.insert_resource(settings.theme.board_asset(&asset_server));
+                next_state.set(AppState::Playing);
+            }
+            EditorAction::Clear => (),
+        }
+    }
+}
+
+/// Forgets where the board was drawn, the puzzle itself is kept for later
+pub fn leave(mut commands: Commands) {
+    commands.remove_resource::<EditorView>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bombs_toggle_and_clear_the_start() {
+        let mut editor = Editor::new(&Map::empty(3, 3));
+        let corner = Coordinates::new(0, 0);
+        editor.toggle_start(corner);
+        assert_eq!(editor.start, Some(corner));
+        editor.cycle_mines(corner);
+        assert_eq!(editor.start, None);
+        assert_eq!(editor.map.bomb_count_at(Coordinates::new(1, 1)), 1);
+        // A bomb cannot be the start tile
+        editor.toggle_start(corner);
+        assert_eq!(editor.start, None);
+        editor.cycle_mines(corner);
+        assert_eq!(editor.map.bomb_count(), 0);
+    }
+
+    #[test]
+    fn mines_cycle_up_to_the_max() {
+        let mut editor = Editor::new(&Map::empty(3, 3).with_max_mines(2));
+        let corner = Coordinates::new(0, 0);
+        editor.cycle_mines(corner);
+        editor.cycle_mines(corner);
+        assert_eq!(editor.map.bomb_count_at(Coordinates::new(1, 1)), 2);
+        editor.cycle_mines(corner);
+        assert_eq!(editor.map.bomb_count(), 0);
+    }
+}
@@ -0,0 +1,317 @@
+use bevy::input::{mouse::MouseButtonInput, ButtonState};
+use bevy::prelude::*;
+use bevy::utils::{HashMap, HashSet};
+
+use crate::bounds::Bounds2;
+use crate::endless::{self, EndlessGame, CHUNK_SIZE};
+use crate::resources::board::TileMark;
+use crate::resources::board_asset::BoardAsset;
+use crate::resources::tile::Tile;
+use crate::resources::topology::Topology;
+
+use super::widgets::text;
+use super::{AppState, MenuFont};
+
+const TILE_SIZE: f32 = 30.;
+const TILE_PADDING: f32 = 2.;
+// Camera speed of the arrow keys, in pixels per second
+const PAN_SPEED: f32 = 600.;
+// Chunks kept around the view, so that panning shows no gap
+const CHUNK_MARGIN: i32 = 1;
+
+/// Chunks drawn around the camera
+#[derive(Debug, Default, Resource)]
+pub struct EndlessView {
+    chunks: HashMap<IVec2, Entity>,
+    /// Tile entities of the drawn chunks
+    tiles: HashMap<IVec2, Entity>,
+    /// Tiles which changed since they were drawn
+    dirty: HashSet<IVec2>,
+    /// Where the camera is heading
+    focus: Vec2,
+}
+
+/// Marker of the endless mode panel entities
+#[derive(Component)]
+pub struct EndlessScreen;
+
+/// Marker of the drawn chunks
+#[derive(Component)]
+pub struct EndlessChunk;
+
+#[derive(Component)]
+pub struct EndlessScoreText;
+
+/// Starts a new endless board, its start tile being uncovered
+pub fn setup(mut commands: Commands) {
+    let mut game = EndlessGame::new(rand::random());
+    game.reveal(EndlessGame::START);
+    commands.insert_resource(game);
+    commands.insert_resource(EndlessView::default());
+}
+
+pub fn spawn(mut commands: Commands, font: Res<MenuFont>) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Percent(100.),
+                padding: UiRect::all(Val::Px(10.)),
+                justify_content: JustifyContent::Center,
+                ..Default::default()
+            },
+            background_color: Color::rgba(0., 0., 0., 0.6).into(),
+            ..Default::default()
+        })
+        .insert((EndlessScreen, Name::new("Endless")))
+        .with_children(|parent| {
+            parent.spawn((text(&font.0, "", 16.), EndlessScoreText));
+        });
+}
+
+/// Left clicks reveal or chord, right clicks flag. Enter starts a new board
+/// once a bomb was hit
+#[allow(clippy::too_many_arguments)]
+pub fn input(
+    mut commands: Commands,
+    window: Query<&Window>,
+    camera: Query<&Transform, With<Camera2d>>,
+    keys: Res<Input<KeyCode>>,
+    mut game: ResMut<EndlessGame>,
+    mut view: ResMut<EndlessView>,
+    chunks: Query<Entity, With<EndlessChunk>>,
+    mut button_evr: EventReader<MouseButtonInput>,
+) {
+    if game.is_over() {
+        button_evr.clear();
+        if keys.just_pressed(KeyCode::Return) {
+            for entity in chunks.iter() {
+                commands.entity(entity).despawn_recursive();
+            }
+            setup(commands);
+        }
+        return;
+    }
+    let window = window.single();
+    let camera = camera.single().translation.truncate();
+    for event in button_evr.iter() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+        let Some(cursor) = window.cursor_position() else {
+            continue;
+        };
+        let window_size = Vec2::new(window.width(), window.height());
+        let position = Bounds2::cursor_to_world(window_size, cursor) + camera;
+        let tile = (position / TILE_SIZE).floor().as_ivec2();
+        let changed = match event.button {
+            MouseButton::Left if game.revealed(tile).is_some() => game.chord(tile),
+            MouseButton::Left => game.reveal(tile),
+            MouseButton::Right if game.toggle_flag(tile) => vec![tile],
+            _ => continue,
+        };
+        if game.is_over() {
+            // The bombs of the drawn chunks are shown
+            let view = &mut *view;
+            let bombs = view
+                .tiles
+                .keys()
+                .copied()
+                .filter(|p| game.map.is_bomb_at(*p));
+            view.dirty.extend(bombs);
+        }
+        view.dirty.extend(changed);
+        // The camera follows moves made near the edges of the window
+        let offset = position - camera;
+        if offset.x.abs() > window_size.x / 4. || offset.y.abs() > window_size.y / 4. {
+            view.focus = position;
+        }
+    }
+}
+
+/// Arrow keys move the camera, which eases towards its focus
+pub fn follow_camera(
+    time: Res<Time>,
+    keys: Res<Input<KeyCode>>,
+    mut view: ResMut<EndlessView>,
+    mut camera: Query<&mut Transform, With<Camera2d>>,
+) {
+    let mut direction = Vec2::ZERO;
+    for (keys_down, delta) in [
+        ([KeyCode::Left, KeyCode::A], Vec2::NEG_X),
+        ([KeyCode::Right, KeyCode::D], Vec2::X),
+        ([KeyCode::Down, KeyCode::S], Vec2::NEG_Y),
+        ([KeyCode::Up, KeyCode::W], Vec2::Y),
+    ] {
+        if keys.any_pressed(keys_down) {
+            direction += delta;
+        }
+    }
+    let mut camera = camera.single_mut();
+    if direction != Vec2::ZERO {
+        view.focus = camera.translation.truncate() + direction * PAN_SPEED * time.delta_seconds();
+        camera.translation = view.focus.extend(camera.translation.z);
+        return;
+    }
+    let t = (time.delta_seconds() * 5.).min(1.);
+    let translation = camera.translation.truncate().lerp(view.focus, t);
+    camera.translation = translation.extend(camera.translation.z);
+}
+
+/// Draws the chunks in view and redraws the changed tiles, despawning the
+/// chunks which left the view
+pub fn stream_chunks(
+    mut commands: Commands,
+    window: Query<&Window>,
+    camera: Query<&Transform, With<Camera2d>>,
+    board_asset: Res<BoardAsset>,
+    mut game: ResMut<EndlessGame>,
+    mut view: ResMut<EndlessView>,
+) {
+    let window = window.single();
+    let center = camera.single().translation.truncate();
+    let half_size = Vec2::new(window.width(), window.height()) / 2.;
+    let chunk_size = CHUNK_SIZE as f32 * TILE_SIZE;
+    let min = ((center - half_size) / chunk_size).floor().as_ivec2() - CHUNK_MARGIN;
+    let max = ((center + half_size) / chunk_size).floor().as_ivec2() + CHUNK_MARGIN;
+    let in_view = |chunk: IVec2| chunk.cmpge(min).all() && chunk.cmple(max).all();
+
+    let view = &mut *view;
+    let tiles = &mut view.tiles;
+    view.chunks.retain(|chunk, entity| {
+        if in_view(*chunk) {
+            return true;
+        }
+        commands.entity(*entity).despawn_recursive();
+        for position in chunk_tiles(*chunk) {
+            tiles.remove(&position);
+        }
+        false
+    });
+    // Tiles of the chunks spawned below are drawn up to date
+    for position in view.dirty.drain() {
+        let (Some(chunk), Some(tile)) = (
+            view.chunks.get(&endless::chunk_of(position)),
+            view.tiles.get_mut(&position),
+        ) else {
+            continue;
+        };
+        commands.entity(*tile).despawn_recursive();
+        *tile = spawn_tile(&mut commands, &board_asset, &mut game, position);
+        commands.entity(*chunk).add_child(*tile);
+    }
+    for y in min.y..=max.y {
+        for x in min.x..=max.x {
+            let chunk = IVec2::new(x, y);
+            if view.chunks.contains_key(&chunk) {
+                continue;
+            }
+            let origin = (chunk * CHUNK_SIZE).as_vec2() * TILE_SIZE;
+            let entity = commands
+                .spawn(SpatialBundle::from_transform(Transform::from_translation(
+                    origin.extend(0.),
+                )))
+                .insert((EndlessChunk, Name::new(format!("Chunk ({}, {})", x, y))))
+                .id();
+            for position in chunk_tiles(chunk) {
+                let tile = spawn_tile(&mut commands, &board_asset, &mut game, position);
+                commands.entity(entity).add_child(tile);
+                view.tiles.insert(position, tile);
+            }
+            view.chunks.insert(chunk, entity);
+        }
+    }
+}
+
+pub fn update_score(game: Res<EndlessGame>, mut texts: Query<&mut Text, With<EndlessScoreText>>) {
+    for mut text in texts.iter_mut() {
+        if game.is_changed() || text.is_added() {
+            text.sections[0].value = if game.is_over() {
+                format!(
+                    "Boom! {} tiles revealed. Enter: new board, Esc: menu",
+                    game.score()
+                )
+            } else {
+                format!("Score: {}   Arrows: move, Esc: menu", game.score())
+            };
+        }
+    }
+}
+
+/// Escape goes back to the main menu
+pub fn leave_shortcut(keys: Res<Input<KeyCode>>, mut next_state: ResMut<NextState<AppState>>) {
+    if keys.just_pressed(KeyCode::Escape) {
+        next_state.set(AppState::MainMenu);
+    }
+}
+
+/// Forgets the board and puts the camera back on the origin
+pub fn leave(mut commands: Commands, mut camera: Query<&mut Transform, With<Camera2d>>) {
+    commands.remove_resource::<EndlessGame>();
+    commands.remove_resource::<EndlessView>();
+    for mut transform in camera.iter_mut() {
+        transform.translation.x = 0.;
+        transform.translation.y = 0.;
+    }
+}
+
+// Positions of the tiles of a chunk
+fn chunk_tiles(chunk: IVec2) -> impl Iterator<Item = IVec2> {
+    (0..CHUNK_SIZE)
+        .flat_map(|y| (0..CHUNK_SIZE).map(move |x| IVec2::new(x, y)))
+        .map(move |local| chunk * CHUNK_SIZE + local)
+}
+
+/// Tile as currently known by the player, relative to its chunk
+fn spawn_tile(
+    commands: &mut Commands,
+    board_asset: &BoardAsset,
+    game: &mut EndlessGame,
+    position: IVec2,
+) -> Entity {
+    let size = TILE_SIZE - TILE_PADDING;
+    let local = position - endless::chunk_of(position) * CHUNK_SIZE;
+    let translation = (local.as_vec2() + 0.5) * TILE_SIZE;
+    let transform = Transform::from_translation(translation.extend(1.));
+    let revealed = game.revealed(position);
+    let exploded = game.exploded() == Some(position);
+    // Bombs are shown once the game is lost
+    let bomb = game.is_over() && game.map.is_bomb_at(position);
+    if revealed.is_none() && !exploded && !bomb {
+        let mut cover = board_asset.cover_bundle(Topology::Square, size);
+        cover.transform = transform;
+        return commands
+            .spawn(cover)
+            .with_children(|parent| {
+                if game.is_flagged(position) {
+                    parent.spawn(board_asset.mark_bundle(TileMark::Flag(1), size));
+                }
+            })
+            .id();
+    }
+    let color = if exploded {
+        Color::RED
+    } else {
+        board_asset.tile_material.color
+    };
+    commands
+        .spawn(SpriteBundle {
+            sprite: Sprite {
+                color,
+                custom_size: Some(Vec2::splat(size)),
+                ..Default::default()
+            },
+            texture: board_asset.tile_material.texture.clone(),
+            transform,
+            ..Default::default()
+        })
+        .with_children(|parent| match revealed {
+            Some(Tile::BombNeighbor(count)) => {
+                parent.spawn(board_asset.bomb_count_text_bundle(count, size));
+            }
+            Some(_) => (),
+            None => {
+                parent.spawn(board_asset.bomb_bundle(size));
+            }
+        })
+        .id()
+}
@@ -0,0 +1,90 @@
+use bevy::prelude::*;
+
+use super::widgets::{screen_root, spawn_button, text};
+use super::{AppState, GameOutcome, MenuAction, MenuFont};
+use crate::campaign::{ActiveLevel, Campaign};
+use crate::systems::event::UndoEvent;
+use crate::systems::input::is_undo_shortcut;
+
+/// Marker of the game over screen entities
+#[derive(Component)]
+pub struct GameOverScreen;
+
+pub fn spawn(
+    mut commands: Commands,
+    font: Res<MenuFont>,
+    outcome: Option<Res<GameOutcome>>,
+    level: Option<Res<ActiveLevel>>,
+    campaign: Res<Campaign>,
+) {
+    let font = &font.0;
+    let (title, won, metrics) = match &outcome {
+        Some(o) if o.won => ("You win!", true, o.metrics),
+        Some(o) => ("Boom!", false, o.metrics),
+        None => ("Game over", false, Default::default()),
+    };
+    let mut lines = vec![
+        format!("Time: {:.1}s", metrics.time.as_secs_f32()),
+        format!("3BV: {}", metrics.three_bv),
+        format!("Clicks: {}", metrics.clicks),
+    ];
+    // Speed metrics only make sense for a solved board
+    if won {
+        lines.push(format!("3BV/s: {:.2}", metrics.three_bv_per_second()));
+        lines.push(format!("Efficiency: {:.0}%", metrics.efficiency() * 100.));
+    }
+    let unlocked = outcome.as_ref().is_some_and(|o| o.won && !o.assisted);
+    if outcome.as_ref().is_some_and(|o| o.assisted) {
+        lines.push("Assisted, not recorded".to_string());
+    }
+    if let Some(line) = outcome.as_ref().and_then(|o| o.level.clone()) {
+        lines.push(line);
+    }
+    let daily = outcome.and_then(|o| o.daily.clone());
+    // Only an unassisted win unlocks the next level
+    let next_level = level
+        .as_ref()
+        .map(|l| l.0 + 1)
+        .filter(|next| unlocked && *next < campaign.levels.len());
+    commands
+        // Translucent so the final board stays visible
+        .spawn(screen_root(Color::rgba(0., 0., 0., 0.6)))
+        .insert((GameOverScreen, Name::new("Game Over")))
+        .with_children(|parent| {
+            parent.spawn(text(font, title, 40.));
+            for line in lines {
+                parent.spawn(text(font, line, 20.));
+            }
+            if let Some(share) = daily {
+                parent.spawn(text(font, "Daily challenge, share it:", 20.));
+                parent.spawn(text(font, share, 14.));
+                spawn_button(parent, font, "Copy result", MenuAction::CopyDailyResult);
+            }
+            if !won {
+                spawn_button(parent, font, "Undo", MenuAction::Undo);
+            }
+            if let Some(next) = next_level {
+                spawn_button(parent, font, "Next level", MenuAction::PlayLevel(next));
+            }
+            if level.is_some() {
+                spawn_button(parent, font, "Levels", MenuAction::OpenCampaign);
+            }
+            spawn_button(parent, font, "New game", MenuAction::NewGame);
+            spawn_button(parent, font, "Copy board code", MenuAction::CopyCode);
+            spawn_button(parent, font, "Main menu", MenuAction::MainMenu);
+            spawn_button(parent, font, "Quit", MenuAction::Quit);
+        });
+}
+
+/// Ctrl+Z on a lost game takes back the losing move
+pub fn undo_shortcut(
+    keys: Res<Input<KeyCode>>,
+    outcome: Option<Res<GameOutcome>>,
+    mut undo_event: EventWriter<UndoEvent>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if is_undo_shortcut(&keys) && outcome.is_some_and(|o| !o.won) {
+        undo_event.send(UndoEvent);
+        next_state.set(AppState::Playing);
+    }
+}
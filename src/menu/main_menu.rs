@@ -0,0 +1,24 @@
+use bevy::prelude::*;
+
+use super::widgets::{screen_root, spawn_button, text};
+use super::{MenuAction, MenuFont};
+
+/// Marker of the main menu entities
+#[derive(Component)]
+pub struct MainMenuScreen;
+
+pub fn spawn(mut commands: Commands, font: Res<MenuFont>) {
+    let font = &font.0;
+    commands
+        .spawn(screen_root(Color::BLACK))
+        .insert((MainMenuScreen, Name::new("Main Menu")))
+        .with_children(|parent| {
+            parent.spawn(text(font, "Mine Sweeper", 40.));
+            spawn_button(parent, font, "New game", MenuAction::ChooseGame);
+            spawn_button(parent, font, "Campaign", MenuAction::OpenCampaign);
+            spawn_button(parent, font, "Editor", MenuAction::OpenEditor);
+            spawn_button(parent, font, "Settings", MenuAction::Settings);
+            spawn_button(parent, font, "Statistics", MenuAction::Stats);
+            spawn_button(parent, font, "Quit", MenuAction::Quit);
+        });
+}
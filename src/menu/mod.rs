@@ -0,0 +1,514 @@
+//! App level screens wrapping the board: main menu, new game, campaign, board
+//! editor, endless mode, settings, pause and game over
+pub mod campaign;
+pub mod editor;
+pub mod endless;
+pub mod game_over;
+pub mod main_menu;
+pub mod new_game;
+pub mod pause;
+pub mod settings;
+pub mod stats;
+mod clipboard;
+mod widgets;
+
+use bevy::app::AppExit;
+use bevy::log;
+use bevy::prelude::*;
+
+use crate::campaign::{ActiveLevel, Campaign, CampaignProgress};
+use crate::daily::{self, DailyChallenge, DailyResult, DailyStore};
+use crate::resources::board::Board;
+use crate::resources::board_options::BoardOptions;
+use crate::resources::difficulty::Difficulty;
+use crate::resources::map::Map;
+use crate::resources::shape::BoardShape;
+use crate::resources::theme::Theme;
+use crate::resources::topology::{Neighborhood, Topology};
+use crate::stats::{GameMetrics, StatsStore};
+use crate::systems::event::{GameLost, GameWon, UndoEvent};
+
+use self::editor::EditorAction;
+use self::new_game::CodeInput;
+
+/// App flow, the board is played in `Playing`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, States)]
+pub enum AppState {
+    #[default]
+    MainMenu,
+    NewGame,
+    Campaign,
+    Editor,
+    Settings,
+    Stats,
+    Playing,
+    Paused,
+    GameOver,
+    /// Board without edges, see `crate::endless`
+    Endless,
+}
+
+/// Options picked in the settings screen, applied on new games
+#[derive(Debug, Clone, Resource)]
+pub struct Settings {
+    pub difficulty: Difficulty,
+    /// Shape of the tiles of random boards
+    pub topology: Topology,
+    /// Neighbors wrap across the edges of random boards
+    pub wrap: bool,
+    /// Neighbors of the square tiles of random boards
+    pub neighborhood: Neighborhood,
+    /// Mines a tile of a random board can hold
+    pub max_mines: u8,
+    /// Playable area of random boards
+    pub shape: BoardShape,
+    pub theme: Theme,
+    /// Bombs which can be hit before losing
+    pub lives: u8,
+    /// Right clicks cycle through a question mark after the flag
+    pub marks: bool,
+}
+
+impl Settings {
+    /// Options of a random board with the chosen rules
+    pub fn board_options(&self) -> BoardOptions {
+        BoardOptions {
+            lives: self.lives,
+            marks: self.marks,
+            topology: self.topology,
+            wrap: self.wrap,
+            neighborhood: self.neighborhood,
+            max_mines: self.max_mines,
+            shape: self.shape,
+            ..self.difficulty.board_options()
+        }
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            difficulty: Default::default(),
+            topology: Default::default(),
+            wrap: false,
+            neighborhood: Default::default(),
+            max_mines: 1,
+            shape: Default::default(),
+            theme: Default::default(),
+            lives: 1,
+            marks: false,
+        }
+    }
+}
+
+/// Result of the last finished game
+#[derive(Debug, Clone, Resource)]
+pub struct GameOutcome {
+    pub won: bool,
+    /// Assisted games are not recorded in the statistics
+    pub assisted: bool,
+    pub metrics: GameMetrics,
+    /// Share line of a completed daily challenge
+    pub daily: Option<String>,
+    /// Result of a campaign level
+    pub level: Option<String>,
+}
+
+/// What a menu button does when pressed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+pub enum MenuAction {
+    /// Opens the new game screen
+    ChooseGame,
+    /// Starts a random board in the selected difficulty
+    NewGame,
+    /// Starts today's challenge board in the selected difficulty
+    DailyGame,
+    /// Starts a board without edges
+    PlayEndless,
+    Settings,
+    Stats,
+    Resume,
+    /// Copies the code of the current board
+    CopyCode,
+    /// Copies the share line of the completed daily challenge
+    CopyDailyResult,
+    /// Copies the share lines of every daily challenge played
+    ExportDailyResults,
+    /// Pastes the clipboard in the board code field
+    PasteCode,
+    /// Starts the board of the typed code
+    PlayCode,
+    /// Opens the level select screen
+    OpenCampaign,
+    /// Starts the campaign level at this index
+    PlayLevel(usize),
+    /// Opens the board editor
+    OpenEditor,
+    /// Handled by the editor screen
+    Editor(EditorAction),
+    /// Takes back the losing move and resumes the game
+    Undo,
+    MainMenu,
+    Quit,
+    SetDifficulty(Difficulty),
+    SetTopology(Topology),
+    SetWrap(bool),
+    SetNeighborhood(Neighborhood),
+    SetMaxMines(u8),
+    SetShape(BoardShape),
+    SetTheme(Theme),
+    SetLives(u8),
+    SetMarks(bool),
+}
+
+impl MenuAction {
+    /// Whether the button matches the current settings
+    fn is_selected(&self, settings: &Settings) -> bool {
+        match self {
+            Self::SetDifficulty(d) => *d == settings.difficulty,
+            Self::SetTopology(t) => *t == settings.topology,
+            Self::SetWrap(w) => *w == settings.wrap,
+            Self::SetNeighborhood(n) => *n == settings.neighborhood,
+            Self::SetMaxMines(m) => *m == settings.max_mines,
+            Self::SetShape(s) => *s == settings.shape,
+            Self::SetTheme(t) => *t == settings.theme,
+            Self::SetLives(l) => *l == settings.lives,
+            Self::SetMarks(m) => *m == settings.marks,
+            _ => false,
+        }
+    }
+}
+
+/// Font shared by every screen
+#[derive(Debug, Clone, Resource)]
+pub struct MenuFont(pub Handle<Font>);
+
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_state::<AppState>()
+            .init_resource::<Settings>()
+            .init_resource::<StatsStore>()
+            .init_resource::<DailyStore>()
+            .init_resource::<CodeInput>()
+            .init_resource::<CampaignProgress>()
+            .add_systems(Startup, (load_font, load_campaign))
+            .add_systems(OnEnter(AppState::MainMenu), main_menu::spawn)
+            .add_systems(
+                OnExit(AppState::MainMenu),
+                despawn_screen::<main_menu::MainMenuScreen>,
+            )
+            .add_systems(OnEnter(AppState::NewGame), new_game::spawn)
+            .add_systems(
+                OnExit(AppState::NewGame),
+                despawn_screen::<new_game::NewGameScreen>,
+            )
+            .add_systems(
+                Update,
+                (new_game::code_typing, new_game::update_code_text)
+                    .chain()
+                    .run_if(in_state(AppState::NewGame)),
+            )
+            .add_systems(OnEnter(AppState::Campaign), campaign::spawn)
+            .add_systems(
+                OnExit(AppState::Campaign),
+                despawn_screen::<campaign::CampaignScreen>,
+            )
+            .add_systems(OnEnter(AppState::Editor), (editor::setup, editor::spawn))
+            .add_systems(
+                OnExit(AppState::Editor),
+                (
+                    despawn_screen::<editor::EditorScreen>,
+                    despawn_screen::<editor::EditorBoard>,
+                    editor::leave,
+                ),
+            )
+            .add_systems(
+                Update,
+                (
+                    editor::input,
+                    editor::editor_action,
+                    editor::render,
+                    editor::update_message,
+                )
+                    .chain()
+                    .run_if(in_state(AppState::Editor)),
+            )
+            .add_systems(OnEnter(AppState::Endless), (endless::setup, endless::spawn))
+            .add_systems(
+                OnExit(AppState::Endless),
+                (
+                    despawn_screen::<endless::EndlessScreen>,
+                    despawn_screen::<endless::EndlessChunk>,
+                    endless::leave,
+                ),
+            )
+            .add_systems(
+                Update,
+                (
+                    endless::input,
+                    endless::follow_camera,
+                    endless::stream_chunks,
+                    endless::update_score,
+                    endless::leave_shortcut,
+                )
+                    .chain()
+                    .run_if(in_state(AppState::Endless)),
+            )
+            .add_systems(OnEnter(AppState::Settings), settings::spawn)
+            .add_systems(
+                OnExit(AppState::Settings),
+                despawn_screen::<settings::SettingsScreen>,
+            )
+            .add_systems(OnEnter(AppState::Stats), stats::spawn)
+            .add_systems(
+                OnExit(AppState::Stats),
+                despawn_screen::<stats::StatsScreen>,
+            )
+            .add_systems(OnEnter(AppState::Paused), (pause::spawn, pause::hide_board))
+            .add_systems(
+                OnExit(AppState::Paused),
+                (despawn_screen::<pause::PauseScreen>, pause::show_board),
+            )
+            .add_systems(OnEnter(AppState::GameOver), game_over::spawn)
+            .add_systems(
+                OnExit(AppState::GameOver),
+                despawn_screen::<game_over::GameOverScreen>,
+            )
+            .add_systems(Update, (button_colors, menu_action))
+            .add_systems(Update, pause::toggle_pause)
+            .add_systems(Update, game_over::undo_shortcut.run_if(in_state(AppState::GameOver)))
+            .add_systems(Update, game_end.run_if(in_state(AppState::Playing)));
+
+        log::info!("Loaded Menu Plugin");
+    }
+}
+
+fn load_font(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(MenuFont(asset_server.load("fonts/pixeled.ttf")));
+}
+
+fn load_campaign(mut commands: Commands) {
+    commands.insert_resource(Campaign::load_asset());
+}
+
+/// Despawns every entity of a screen
+fn despawn_screen<T: Component>(mut commands: Commands, screens: Query<Entity, With<T>>) {
+    for entity in screens.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn button_colors(
+    settings: Res<Settings>,
+    mut buttons: Query<(&Interaction, &MenuAction, &mut BackgroundColor), With<Button>>,
+) {
+    for (interaction, action, mut color) in buttons.iter_mut() {
+        *color = widgets::button_color(*interaction, action.is_selected(&settings)).into();
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn menu_action(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut settings: ResMut<Settings>,
+    board: Option<Res<Board>>,
+    campaign: Res<Campaign>,
+    outcome: Option<Res<GameOutcome>>,
+    daily_store: Res<DailyStore>,
+    buttons: Query<(&Interaction, &MenuAction), Changed<Interaction>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut code_input: ResMut<CodeInput>,
+    mut undo_event: EventWriter<UndoEvent>,
+    mut exit: EventWriter<AppExit>,
+) {
+    for (interaction, action) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match action {
+            MenuAction::ChooseGame => next_state.set(AppState::NewGame),
+            MenuAction::NewGame => {
+                // A paused or finished board must not be resumed
+                if let Some(board) = &board {
+                    board.despawn(&mut commands);
+                }
+                commands.insert_resource(settings.board_options());
+                commands.insert_resource(settings.theme.board_asset(&asset_server));
+                commands.remove_resource::<DailyChallenge>();
+                commands.remove_resource::<ActiveLevel>();
+                next_state.set(AppState::Playing);
+            }
+            MenuAction::DailyGame => {
+                if let Some(board) = &board {
+                    board.despawn(&mut commands);
+                }
+                // Classic rules so every result compares
+                let daily = DailyChallenge {
+                    date: daily::today(),
+                    difficulty: settings.difficulty,
+                };
+                commands.insert_resource(BoardOptions {
+                    seed: Some(daily::daily_seed(daily.date, daily.difficulty)),
+                    marks: settings.marks,
+                    ..daily.difficulty.board_options()
+                });
+                commands.insert_resource(settings.theme.board_asset(&asset_server));
+                commands.insert_resource(daily);
+                commands.remove_resource::<ActiveLevel>();
+                next_state.set(AppState::Playing);
+            }
+            MenuAction::Settings => next_state.set(AppState::Settings),
+            MenuAction::Stats => next_state.set(AppState::Stats),
+            MenuAction::Resume => next_state.set(AppState::Playing),
+            MenuAction::CopyCode => {
+                if let Some(board) = &board {
+                    clipboard::copy(&board.code());
+                }
+            }
+            MenuAction::CopyDailyResult => {
+                if let Some(share) = outcome.as_ref().and_then(|o| o.daily.as_ref()) {
+                    clipboard::copy(share);
+                }
+            }
+            MenuAction::ExportDailyResults => clipboard::copy(&daily_store.export()),
+            MenuAction::PasteCode => {
+                if let Some(code) = clipboard::paste() {
+                    code_input.code = code.trim().to_string();
+                    code_input.error = None;
+                }
+            }
+            MenuAction::PlayCode => match Map::decode(&code_input.code) {
+                Ok(_) => {
+                    if let Some(board) = &board {
+                        board.despawn(&mut commands);
+                    }
+                    commands.insert_resource(BoardOptions {
+                        layout: Some(code_input.code.clone()),
+                        ..settings.board_options()
+                    });
+                    commands.insert_resource(settings.theme.board_asset(&asset_server));
+                    commands.remove_resource::<DailyChallenge>();
+                    commands.remove_resource::<ActiveLevel>();
+                    code_input.error = None;
+                    next_state.set(AppState::Playing);
+                }
+                Err(e) => code_input.error = Some(e.to_string()),
+            },
+            MenuAction::PlayEndless => {
+                if let Some(board) = &board {
+                    board.despawn(&mut commands);
+                }
+                commands.insert_resource(settings.theme.board_asset(&asset_server));
+                next_state.set(AppState::Endless);
+            }
+            MenuAction::OpenCampaign => next_state.set(AppState::Campaign),
+            MenuAction::PlayLevel(i) => {
+                let Some(level) = campaign.levels.get(*i) else {
+                    continue;
+                };
+                if let Some(board) = &board {
+                    board.despawn(&mut commands);
+                }
+                commands.insert_resource(BoardOptions {
+                    marks: settings.marks,
+                    ..level.options.clone()
+                });
+                commands.insert_resource(settings.theme.board_asset(&asset_server));
+                commands.remove_resource::<DailyChallenge>();
+                commands.insert_resource(ActiveLevel(*i));
+                next_state.set(AppState::Playing);
+            }
+            MenuAction::OpenEditor => next_state.set(AppState::Editor),
+            MenuAction::Editor(_) => (),
+            MenuAction::Undo => {
+                undo_event.send(UndoEvent);
+                next_state.set(AppState::Playing);
+            }
+            MenuAction::MainMenu => next_state.set(AppState::MainMenu),
+            MenuAction::Quit => exit.send(AppExit),
+            MenuAction::SetDifficulty(d) => settings.difficulty = *d,
+            MenuAction::SetTopology(t) => settings.topology = *t,
+            MenuAction::SetWrap(w) => settings.wrap = *w,
+            MenuAction::SetNeighborhood(n) => settings.neighborhood = *n,
+            MenuAction::SetMaxMines(m) => settings.max_mines = *m,
+            MenuAction::SetShape(s) => settings.shape = *s,
+            MenuAction::SetTheme(t) => settings.theme = *t,
+            MenuAction::SetLives(l) => settings.lives = *l,
+            MenuAction::SetMarks(m) => settings.marks = *m,
+        }
+    }
+}
+
+/// Records the game and leaves the board once it is over
+#[allow(clippy::too_many_arguments)]
+fn game_end(
+    mut commands: Commands,
+    mut won: EventReader<GameWon>,
+    mut lost: EventReader<GameLost>,
+    board: Res<Board>,
+    settings: Res<Settings>,
+    board_options: Res<BoardOptions>,
+    mut stats: ResMut<StatsStore>,
+    daily: Option<Res<DailyChallenge>>,
+    mut daily_store: ResMut<DailyStore>,
+    level: Option<Res<ActiveLevel>>,
+    campaign: Res<Campaign>,
+    mut progress: ResMut<CampaignProgress>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let (won, time) = if let Some(event) = won.iter().last() {
+        (true, event.time)
+    } else if lost.iter().last().is_some() {
+        (false, board.timer.elapsed())
+    } else {
+        return;
+    };
+    let mut outcome = GameOutcome {
+        won,
+        assisted: board.assisted,
+        metrics: GameMetrics {
+            time,
+            three_bv: board.tile_map.three_bv(),
+            clicks: board.clicks,
+        },
+        daily: None,
+        level: None,
+    };
+    if let Some(daily) = daily.filter(|_| outcome.won && !outcome.assisted) {
+        let best = daily_store.record(DailyResult {
+            date: daily.date,
+            difficulty: daily.difficulty,
+            metrics: outcome.metrics,
+        });
+        log::info!("{}", best.share_text());
+        outcome.daily = Some(best.share_text());
+    }
+    let level = level.and_then(|l| campaign.levels.get(l.0));
+    if let Some(level) = level.filter(|_| outcome.won && !outcome.assisted) {
+        let new_best = progress.record(level, outcome.metrics.time);
+        outcome.level = Some(format!(
+            "{} done, par {}s{}",
+            level.title,
+            level.par,
+            if new_best { ", new best" } else { "" }
+        ));
+    }
+    if outcome.assisted {
+        log::info!("Assisted game, statistics left untouched");
+    } else if level.is_some() {
+        log::info!("Campaign level, statistics left untouched");
+    } else if board_options.layout.is_some() {
+        log::info!("Board played from a code, statistics left untouched");
+    } else if !board_options.is_classic_grid() {
+        log::info!("Board variant, statistics left untouched");
+    } else if outcome.won {
+        stats.record_win(settings.difficulty, outcome.metrics, board.seed);
+    } else {
+        stats.record_loss(settings.difficulty);
+    }
+    commands.insert_resource(outcome);
+    next_state.set(AppState::GameOver);
+}
@@ -0,0 +1,113 @@
+use bevy::prelude::*;
+use bevy::text::BreakLineOn;
+
+use super::widgets::{row, screen_root, spawn_button, text};
+use super::{clipboard, MenuAction, MenuFont, Settings};
+
+/// Marker of the new game screen entities
+#[derive(Component)]
+pub struct NewGameScreen;
+
+/// Board code typed or pasted in the new game screen
+#[derive(Debug, Default, Resource)]
+pub struct CodeInput {
+    pub code: String,
+    /// Why the code could not be played
+    pub error: Option<String>,
+}
+
+#[derive(Component)]
+pub struct CodeText;
+
+#[derive(Component)]
+pub struct CodeErrorText;
+
+pub fn spawn(
+    mut commands: Commands,
+    font: Res<MenuFont>,
+    settings: Res<Settings>,
+    input: Res<CodeInput>,
+) {
+    let font = &font.0;
+    let mut code_text = text(font, code_label(&input), 12.);
+    // Codes have no spaces to break lines on
+    code_text.text.linebreak_behavior = BreakLineOn::AnyCharacter;
+    commands
+        .spawn(screen_root(Color::BLACK))
+        .insert((NewGameScreen, Name::new("New Game")))
+        .with_children(|parent| {
+            parent.spawn(text(font, "New game", 40.));
+            parent.spawn(text(font, settings.difficulty.label(), 20.));
+            spawn_button(parent, font, "Random board", MenuAction::NewGame);
+            spawn_button(parent, font, "Daily challenge", MenuAction::DailyGame);
+            spawn_button(parent, font, "Endless", MenuAction::PlayEndless);
+            parent.spawn(text(font, "Board code", 24.));
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Percent(90.),
+                        min_height: Val::Px(40.),
+                        padding: UiRect::all(Val::Px(10.)),
+                        ..Default::default()
+                    },
+                    background_color: Color::rgb(0.1, 0.1, 0.1).into(),
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    parent.spawn((code_text, CodeText));
+                });
+            let error = input.error.clone().unwrap_or_default();
+            parent.spawn((text(font, error, 12.), CodeErrorText));
+            parent.spawn(row()).with_children(|parent| {
+                spawn_button(parent, font, "Paste", MenuAction::PasteCode);
+                spawn_button(parent, font, "Play code", MenuAction::PlayCode);
+            });
+            spawn_button(parent, font, "Back", MenuAction::MainMenu);
+        });
+}
+
+/// Typing in the code field, Ctrl+V pasting the clipboard
+pub fn code_typing(
+    keys: Res<Input<KeyCode>>,
+    mut characters: EventReader<ReceivedCharacter>,
+    mut input: ResMut<CodeInput>,
+) {
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    if ctrl && keys.just_pressed(KeyCode::V) {
+        if let Some(code) = clipboard::paste() {
+            input.code = code;
+        }
+    }
+    if keys.just_pressed(KeyCode::Back) {
+        input.code.pop();
+    }
+    for event in characters.iter() {
+        if !ctrl && !event.char.is_control() && !event.char.is_whitespace() {
+            input.code.push(event.char);
+        }
+    }
+}
+
+pub fn update_code_text(
+    input: Res<CodeInput>,
+    mut code_text: Query<&mut Text, (With<CodeText>, Without<CodeErrorText>)>,
+    mut error_text: Query<&mut Text, With<CodeErrorText>>,
+) {
+    if !input.is_changed() {
+        return;
+    }
+    for mut text in code_text.iter_mut() {
+        text.sections[0].value = code_label(&input);
+    }
+    for mut text in error_text.iter_mut() {
+        text.sections[0].value = input.error.clone().unwrap_or_default();
+    }
+}
+
+fn code_label(input: &CodeInput) -> String {
+    if input.code.is_empty() {
+        "Type or paste a code".to_string()
+    } else {
+        input.code.clone()
+    }
+}
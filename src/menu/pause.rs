@@ -0,0 +1,62 @@
+use bevy::prelude::*;
+
+use crate::resources::board::Board;
+
+use super::widgets::{screen_root, spawn_button, text};
+use super::{AppState, MenuAction, MenuFont};
+
+/// Marker of the pause menu entities
+#[derive(Component)]
+pub struct PauseScreen;
+
+pub fn spawn(mut commands: Commands, font: Res<MenuFont>) {
+    let font = &font.0;
+    commands
+        .spawn(screen_root(Color::BLACK))
+        .insert((PauseScreen, Name::new("Pause Menu")))
+        .with_children(|parent| {
+            parent.spawn(text(font, "Paused", 40.));
+            spawn_button(parent, font, "Resume", MenuAction::Resume);
+            spawn_button(parent, font, "New game", MenuAction::NewGame);
+            spawn_button(parent, font, "Copy board code", MenuAction::CopyCode);
+            spawn_button(parent, font, "Main menu", MenuAction::MainMenu);
+            spawn_button(parent, font, "Quit", MenuAction::Quit);
+        });
+}
+
+/// Escape pauses and resumes the game
+pub fn toggle_pause(
+    keys: Res<Input<KeyCode>>,
+    state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if !keys.just_pressed(KeyCode::Escape) {
+        return;
+    }
+    match state.get() {
+        AppState::Playing => next_state.set(AppState::Paused),
+        AppState::Paused => next_state.set(AppState::Playing),
+        _ => (),
+    }
+}
+
+// The board stays hidden while paused so it can't be studied
+pub fn hide_board(board: Option<Res<Board>>, mut visibilities: Query<&mut Visibility>) {
+    set_board_visibility(board, &mut visibilities, Visibility::Hidden);
+}
+
+pub fn show_board(board: Option<Res<Board>>, mut visibilities: Query<&mut Visibility>) {
+    set_board_visibility(board, &mut visibilities, Visibility::Inherited);
+}
+
+fn set_board_visibility(
+    board: Option<Res<Board>>,
+    visibilities: &mut Query<&mut Visibility>,
+    visibility: Visibility,
+) {
+    if let Some(board) = board {
+        if let Ok(mut v) = visibilities.get_mut(board.entity) {
+            *v = visibility;
+        }
+    }
+}
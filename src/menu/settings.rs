@@ -0,0 +1,74 @@
+use bevy::prelude::*;
+
+use crate::resources::difficulty::Difficulty;
+use crate::resources::shape::BoardShape;
+use crate::resources::theme::Theme;
+use crate::resources::topology::{Neighborhood, Topology};
+
+use super::widgets::{row, screen_root, spawn_button, spawn_choice_button, text};
+use super::{MenuAction, MenuFont};
+
+/// Lives which can be picked, `1` being the classic rules
+pub const LIVES: [u8; 3] = [1, 3, 5];
+
+/// Mines a tile can hold which can be picked, `1` being the classic rules
+pub const MAX_MINES: [u8; 3] = [1, 2, 3];
+
+/// Marker of the settings screen entities
+#[derive(Component)]
+pub struct SettingsScreen;
+
+pub fn spawn(mut commands: Commands, font: Res<MenuFont>) {
+    let font = &font.0;
+    commands
+        .spawn(screen_root(Color::BLACK))
+        .insert((SettingsScreen, Name::new("Settings")))
+        .with_children(|parent| {
+            parent.spawn(text(font, "Settings", 40.));
+            let difficulties = Difficulty::ALL.map(|d| (d.label(), MenuAction::SetDifficulty(d)));
+            spawn_setting(parent, font, "Difficulty", difficulties);
+            let topologies = Topology::ALL.map(|t| (t.label(), MenuAction::SetTopology(t)));
+            spawn_setting(parent, font, "Grid", topologies);
+            let shapes = BoardShape::ALL.map(|s| (s.label(), MenuAction::SetShape(s)));
+            spawn_setting(parent, font, "Shape", shapes);
+            let neighborhoods =
+                Neighborhood::ALL.map(|n| (n.label(), MenuAction::SetNeighborhood(n)));
+            spawn_setting(parent, font, "Neighbors", neighborhoods);
+            let wrap = [("Off", false), ("On", true)].map(|(l, w)| (l, MenuAction::SetWrap(w)));
+            spawn_setting(parent, font, "Wrap edges", wrap);
+            let max_mines = MAX_MINES.map(|m| (m.to_string(), MenuAction::SetMaxMines(m)));
+            spawn_setting(parent, font, "Mines per tile", max_mines);
+            let themes = Theme::ALL.map(|t| (t.label(), MenuAction::SetTheme(t)));
+            spawn_setting(parent, font, "Theme", themes);
+            let lives = LIVES.map(|l| (l.to_string(), MenuAction::SetLives(l)));
+            spawn_setting(parent, font, "Lives", lives);
+            let marks = [("Off", false), ("On", true)].map(|(l, m)| (l, MenuAction::SetMarks(m)));
+            spawn_setting(parent, font, "Question marks", marks);
+            spawn_button(parent, font, "Back", MenuAction::MainMenu);
+        });
+}
+
+/// Row of a setting: its name then a button per choice
+fn spawn_setting<L: Into<String>>(
+    parent: &mut ChildBuilder,
+    font: &Handle<Font>,
+    name: &str,
+    choices: impl IntoIterator<Item = (L, MenuAction)>,
+) {
+    parent.spawn(row()).with_children(|parent| {
+        parent
+            .spawn(NodeBundle {
+                style: Style {
+                    width: Val::Px(170.),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .with_children(|parent| {
+                parent.spawn(text(font, name, 16.));
+            });
+        for (label, action) in choices {
+            spawn_choice_button(parent, font, label, action);
+        }
+    });
+}
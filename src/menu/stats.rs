@@ -0,0 +1,91 @@
+use bevy::prelude::*;
+
+use crate::daily::DailyStore;
+use crate::resources::difficulty::Difficulty;
+use crate::stats::StatsStore;
+
+use super::widgets::{row, screen_root, spawn_button, text};
+use super::{MenuAction, MenuFont};
+
+/// Marker of the statistics screen entities
+#[derive(Component)]
+pub struct StatsScreen;
+
+pub fn spawn(
+    mut commands: Commands,
+    font: Res<MenuFont>,
+    store: Res<StatsStore>,
+    daily_store: Res<DailyStore>,
+) {
+    let font = &font.0;
+    commands
+        .spawn(screen_root(Color::BLACK))
+        .insert((StatsScreen, Name::new("Statistics")))
+        .with_children(|parent| {
+            parent.spawn(text(font, "Statistics", 40.));
+            parent.spawn(row()).with_children(|parent| {
+                for difficulty in Difficulty::ALL {
+                    spawn_column(parent, font, &store, difficulty);
+                }
+            });
+            if !daily_store.results.is_empty() {
+                parent.spawn(text(
+                    font,
+                    format!("Daily challenges played: {}", daily_store.results.len()),
+                    20.,
+                ));
+                spawn_button(
+                    parent,
+                    font,
+                    "Copy daily results",
+                    MenuAction::ExportDailyResults,
+                );
+            }
+            spawn_button(parent, font, "Back", MenuAction::MainMenu);
+        });
+}
+
+/// Stats and best times of a single difficulty
+fn spawn_column(
+    parent: &mut ChildBuilder,
+    font: &Handle<Font>,
+    store: &StatsStore,
+    difficulty: Difficulty,
+) {
+    let stats = store.stats.get(difficulty).cloned().unwrap_or_default();
+    let mut lines = vec![
+        format!("Played: {}", stats.played),
+        format!("Won: {}", stats.won),
+        format!("Win rate: {:.0}%", stats.win_rate() * 100.),
+        format!("Streak: {}", stats.current_streak),
+        format!("Best streak: {}", stats.best_streak),
+        String::new(),
+        "Best times".to_string(),
+    ];
+    lines.extend(stats.best_times.iter().enumerate().map(|(i, best)| {
+        format!(
+            "{}. {:.2}s {:.2} 3BV/s {} #{}",
+            i + 1,
+            best.time.as_secs_f32(),
+            best.metrics().three_bv_per_second(),
+            best.date,
+            best.seed
+        )
+    }));
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Column,
+                min_width: Val::Px(200.),
+                row_gap: Val::Px(4.),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            parent.spawn(text(font, difficulty.label(), 20.));
+            for line in lines {
+                parent.spawn(text(font, line, 10.));
+            }
+        });
+}
@@ -0,0 +1,102 @@
+use bevy::prelude::*;
+
+use super::MenuAction;
+
+const NORMAL_BUTTON: Color = Color::rgb(0.15, 0.15, 0.15);
+const HOVERED_BUTTON: Color = Color::rgb(0.25, 0.25, 0.25);
+const PRESSED_BUTTON: Color = Color::rgb(0.35, 0.75, 0.35);
+const SELECTED_BUTTON: Color = Color::rgb(0.25, 0.55, 0.25);
+
+pub(super) fn button_color(interaction: Interaction, selected: bool) -> Color {
+    match (interaction, selected) {
+        (Interaction::Pressed, _) => PRESSED_BUTTON,
+        (Interaction::Hovered, _) => HOVERED_BUTTON,
+        (Interaction::None, true) => SELECTED_BUTTON,
+        (Interaction::None, false) => NORMAL_BUTTON,
+    }
+}
+
+/// Full window root node centering its children in a column
+pub(super) fn screen_root(background: Color) -> NodeBundle {
+    NodeBundle {
+        style: Style {
+            width: Val::Percent(100.),
+            height: Val::Percent(100.),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            row_gap: Val::Px(10.),
+            ..Default::default()
+        },
+        background_color: background.into(),
+        ..Default::default()
+    }
+}
+
+/// Horizontal group of widgets
+pub(super) fn row() -> NodeBundle {
+    NodeBundle {
+        style: Style {
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(10.),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+pub(super) fn text(font: &Handle<Font>, value: impl Into<String>, font_size: f32) -> TextBundle {
+    TextBundle::from_section(
+        value,
+        TextStyle {
+            font: font.clone(),
+            font_size,
+            color: Color::WHITE,
+        },
+    )
+}
+
+pub(super) fn spawn_button(
+    parent: &mut ChildBuilder,
+    font: &Handle<Font>,
+    label: impl Into<String>,
+    action: MenuAction,
+) {
+    spawn_sized_button(parent, font, label, action, 180.);
+}
+
+/// Narrower button, for rows of choices
+pub(super) fn spawn_choice_button(
+    parent: &mut ChildBuilder,
+    font: &Handle<Font>,
+    label: impl Into<String>,
+    action: MenuAction,
+) {
+    spawn_sized_button(parent, font, label, action, 100.);
+}
+
+fn spawn_sized_button(
+    parent: &mut ChildBuilder,
+    font: &Handle<Font>,
+    label: impl Into<String>,
+    action: MenuAction,
+    min_width: f32,
+) {
+    parent
+        .spawn(ButtonBundle {
+            style: Style {
+                min_width: Val::Px(min_width),
+                padding: UiRect::all(Val::Px(10.)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            background_color: NORMAL_BUTTON.into(),
+            ..Default::default()
+        })
+        .insert(action)
+        .with_children(|parent| {
+            parent.spawn(text(font, label, 16.));
+        });
+}
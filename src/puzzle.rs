@@ -0,0 +1,47 @@
+//! Hand-crafted boards, authored in the editor and saved as RON
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::components::coordinates::Coordinates;
+use crate::resources::board_code::CodeError;
+use crate::resources::map::Map;
+use crate::stats::{read_ron, write_ron};
+
+/// Board layout with the tile to uncover first
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Puzzle {
+    pub name: String,
+    /// Layout and start tile, see `Map::encode`
+    pub code: String,
+}
+
+impl Puzzle {
+    pub fn new(name: impl Into<String>, map: &Map, start: Coordinates) -> Self {
+        Self {
+            name: name.into(),
+            code: map.encode(Some(start)),
+        }
+    }
+
+    pub fn map(&self) -> Result<(Map, Option<Coordinates>), CodeError> {
+        Map::decode(&self.code)
+    }
+
+    /// `puzzles` in the platform data directory
+    pub fn default_dir() -> PathBuf {
+        dirs::data_dir()
+            .unwrap_or_default()
+            .join("mine")
+            .join("puzzles")
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        read_ron(path)
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        write_ron(path, self)
+    }
+}
@@ -1,67 +1,237 @@
 use bevy::prelude::*;
-use bevy::utils::HashMap;
+use bevy::time::Stopwatch;
+use bevy::utils::{HashMap, HashSet};
 // use bevy::log;
 
 use crate::bounds::Bounds2;
 use crate::components::coordinates::Coordinates;
+use crate::systems::hint::ActiveHint;
 
+use super::history::History;
 use super::map::Map;
 
+/// Mark put by the player on a covered tile
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TileMark {
+    /// Known bomb, blocking reveals, with the mines the tile is believed
+    /// to hold
+    Flag(u8),
+    /// Unsure, only a reminder for the player
+    Question,
+}
+
+/// Progress of the game on the board
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GameStatus {
+    /// The player can still interact with the board
+    #[default]
+    Playing,
+    /// Every safe tile has been uncovered
+    Won,
+    /// A bomb has been uncovered
+    Lost,
+}
+
+/// Main board resource, inserted by `BoardPlugin::create_board`
 #[derive(Debug, Resource)]
 pub struct Board {
+    /// Hidden tile map
     pub tile_map: Map,
+    /// Board bounds in world space
     pub bounds: Bounds2,
+    /// Tile world size
     pub tile_size: f32,
+    /// Cover entities of the tiles which are still covered
     pub covered_tiles: HashMap<Coordinates, Entity>,
+    /// Flag entities of the flagged covered tiles, along with their mines
+    pub flagged_tiles: HashMap<Coordinates, (Entity, u8)>,
+    /// Question mark entities of the covered tiles marked as unsure
+    pub question_tiles: HashMap<Coordinates, Entity>,
+    /// Bombs hit, uncovered and known from then on, the one losing the
+    /// game included
+    pub exploded_tiles: HashSet<Coordinates>,
+    /// Bombs which can still be hit, the game being lost at zero
+    pub lives: u8,
+    /// Root board entity
     pub entity: Entity,
+    /// Seed the bombs were placed with
+    pub seed: u64,
+    /// Tile uncovered when the game started
+    pub start: Option<Coordinates>,
+    /// Progress of the game
+    pub status: GameStatus,
+    /// Time spent playing the board
+    pub timer: Stopwatch,
+    /// Clicks made on the board
+    pub clicks: u32,
+    /// Whether the bot, a hint, the probability overlay, an undo or an extra
+    /// life helped, keeping the game off the statistics
+    pub assisted: bool,
+    /// Actions which can be undone
+    pub history: History,
 }
 
 impl Board {
     /// Translates a mouse position to board coordinates
     pub fn mouse_position(&self, window: &Window, position: Vec2) -> Option<Coordinates> {
         let windows_size = Vec2::new(window.width(), window.height());
-        let position = Vec2 {
-            x: position.x - windows_size.x / 2.,
-            y: position.y - windows_size.y / 2.,
-        };
+        let position = Bounds2::cursor_to_world(windows_size, position);
         // Bounds check
         if !self.bounds.is_bounds(position) {
             return None;
         }
         // Turn world space to board space
         let coordinates = position - self.bounds.position;
-        Some(Coordinates { 
-            x: (coordinates.x / self.tile_size) as u16, 
-            y: (coordinates.y / self.tile_size) as u16,
-        })
+        self.tile_map
+            .topology()
+            .tile_at(coordinates, self.tile_size)
+            .filter(|c| self.tile_map.contains(*c))
     }
 
-    /// Retiries a covered tile entity
+    /// Center of a tile relative to the board entity
+    pub fn tile_translation(&self, coords: Coordinates) -> Vec2 {
+        self.tile_map.topology().tile_center(coords, self.tile_size)
+    }
+
+    /// Retiries a covered tile entity, flagged tiles can't be uncovered
     pub fn tile_to_uncover(&self, coords: &Coordinates) -> Option<&Entity> {
         // log::info!("covered_tile: {:?}", self.covered_tiles);
+        if self.is_flagged(coords) {
+            return None;
+        }
         self.covered_tiles.get(coords)
     }
 
     /// Trying to uncover a tile
     pub fn try_uncover_tile(&mut self, coords: &Coordinates) -> Option<Entity> {
+        self.flagged_tiles.remove(coords);
+        self.question_tiles.remove(coords);
         self.covered_tiles.remove(coords)
     }
 
-    /// Rerieve the adjacent covered tile entities
+    /// Rerieve the adjacent covered tile entities, skipping the flagged ones
     pub fn adjacent_covered_tiles(&self, coords: Coordinates) -> Vec<Entity> {
         self.tile_map
-            .safe_square_at(coords)
-            .filter_map(|c| self.covered_tiles.get(&c))
+            .neighbors(coords)
+            .filter_map(|c| self.tile_to_uncover(&c))
             .copied()
             .collect()
     }
 
+    /// Sums the mines flagged around the given coordinates, those of the
+    /// exploded bombs included
+    pub fn adjacent_flag_count(&self, coords: Coordinates) -> usize {
+        self.tile_map
+            .neighbors(coords)
+            .map(|c| match self.flagged_tiles.get(&c) {
+                Some((_, mines)) => *mines as usize,
+                None if self.exploded_tiles.contains(&c) => self.tile_map.mines_at(c) as usize,
+                None => 0,
+            })
+            .sum()
+    }
+
+    /// Is the tile still covered
+    pub fn is_covered(&self, coords: &Coordinates) -> bool {
+        self.covered_tiles.contains_key(coords)
+    }
+
+    /// Mark of a covered tile
+    pub fn mark(&self, coords: &Coordinates) -> Option<TileMark> {
+        if let Some((_, mines)) = self.flagged_tiles.get(coords) {
+            Some(TileMark::Flag(*mines))
+        } else if self.question_tiles.contains_key(coords) {
+            Some(TileMark::Question)
+        } else {
+            None
+        }
+    }
+
+    /// Code of the board layout, to play it again or share it
+    pub fn code(&self) -> String {
+        self.tile_map.encode(self.start)
+    }
+
+    /// Is the tile flagged
+    pub fn is_flagged(&self, coords: &Coordinates) -> bool {
+        self.flagged_tiles.contains_key(coords)
+    }
+
+    /// Is the game still running
+    pub fn is_playing(&self) -> bool {
+        self.status == GameStatus::Playing
+    }
+
+    /// Whether only bombs remain covered. Exploded bombs count as resolved
+    pub fn is_completed(&self) -> bool {
+        self.covered_tiles
+            .keys()
+            .all(|c| self.tile_map.is_bomb_at(*c))
+    }
+
+    /// Despawns the board entity and removes the resource, letting the next
+    /// `BoardPlugin::create_board` start a new game
+    pub fn despawn(&self, commands: &mut Commands) {
+        commands.entity(self.entity).despawn_recursive();
+        commands.remove_resource::<Board>();
+        commands.remove_resource::<ActiveHint>();
+    }
+
     pub fn get_coords(&self, entiry: &Entity) -> Option<&Coordinates> {
         for (co, en) in self.covered_tiles.iter() {
-            if en.eq(&entiry) {
+            if en.eq(entiry) {
                 return Some(co);
             }
         }
-        return None;
+        None
     }
-}   
\ No newline at end of file
+}
+
+#[cfg(test)]
+impl Board {
+    /// Board of the given map with every tile covered, the tiles and their
+    /// covers being spawned in `world`
+    pub fn spawn_for_tests(world: &mut World, tile_map: Map) -> Self {
+        use crate::components::bomb::Bomb;
+        use crate::components::bomb_neighber::BombNeighbor;
+        use crate::resources::tile::Tile;
+
+        let mut covered_tiles = HashMap::default();
+        for (coords, tile) in tile_map.iter() {
+            let cover = world.spawn_empty().id();
+            let mut entity = world.spawn(coords);
+            match tile {
+                Tile::Bomb(_) => {
+                    entity.insert(Bomb);
+                }
+                Tile::BombNeighbor(count) => {
+                    entity.insert(BombNeighbor { count: *count });
+                }
+                _ => (),
+            }
+            entity.push_children(&[cover]);
+            covered_tiles.insert(coords, cover);
+        }
+        Self {
+            tile_map,
+            bounds: Bounds2 {
+                position: Vec2::ZERO,
+                size: Vec2::ZERO,
+            },
+            tile_size: 1.,
+            covered_tiles,
+            flagged_tiles: HashMap::default(),
+            question_tiles: HashMap::default(),
+            exploded_tiles: Default::default(),
+            lives: 1,
+            entity: world.spawn_empty().id(),
+            seed: 0,
+            start: None,
+            status: GameStatus::Playing,
+            timer: Default::default(),
+            clicks: 0,
+            assisted: false,
+            history: Default::default(),
+        }
+    }
+}
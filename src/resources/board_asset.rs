@@ -1,5 +1,8 @@
 use bevy::prelude::*;
 use bevy_render::texture::DEFAULT_IMAGE_HANDLE;
+
+use super::board::TileMark;
+use super::topology::Topology;
 // 所有的资源从这里进行控制
 
 #[derive(Debug, Clone)]
@@ -26,7 +29,10 @@ pub struct BoardAsset {
     pub bomb_counter_font: Handle<Font>,
     pub bomb_counter_colors: Vec<Color>,
     pub flag_material: SpriteMaterial,
+    pub question_material: SpriteMaterial,
     pub bomb_material: SpriteMaterial,
+    /// White hexagon used instead of the tile textures on hex boards
+    pub hex_texture: Handle<Image>,
 }
 
 impl BoardAsset {
@@ -49,4 +55,114 @@ impl BoardAsset {
             None => Color::WHITE,
         }
     }
-}
\ No newline at end of file
+
+    /// Bomb counter text of a tile, child of the tile sprite
+    pub fn bomb_count_text_bundle(&self, count: u8, size: f32) -> Text2dBundle {
+        // Two digits, from wider neighborhoods, must still fit the tile
+        let font_size = if count >= 10 { size * 0.6 } else { size };
+        Text2dBundle {
+            text: Text::from_section(
+                count.to_string(),
+                TextStyle {
+                    font: self.bomb_counter_font.clone(),
+                    font_size,
+                    color: self.bomb_counter_color(count),
+                },
+            )
+            .with_alignment(TextAlignment::Center),
+            transform: Transform::from_xyz(0., 0., 1.),
+            ..Default::default()
+        }
+    }
+
+    /// Mines of a tile holding several, or of a flag standing for several,
+    /// in the corner of the bomb or flag sprite
+    pub fn mine_count_text_bundle(&self, count: u8, size: f32) -> Text2dBundle {
+        Text2dBundle {
+            text: Text::from_section(
+                count.to_string(),
+                TextStyle {
+                    font: self.bomb_counter_font.clone(),
+                    font_size: size * 0.5,
+                    color: Color::RED,
+                },
+            )
+            .with_alignment(TextAlignment::Center),
+            transform: Transform::from_xyz(size * 0.3, -size * 0.3, 0.5),
+            ..Default::default()
+        }
+    }
+
+    /// Bomb sprite of a tile, child of the tile sprite
+    pub fn bomb_bundle(&self, size: f32) -> SpriteBundle {
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::BLACK,
+                custom_size: Some(Vec2::splat(size)),
+                ..Default::default()
+            },
+            transform: Transform::from_xyz(0., 0., 1.),
+            texture: self.bomb_material.texture.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Texture of a tile sprite in the given tile shape
+    pub fn tile_texture(&self, material: &SpriteMaterial, topology: Topology) -> Handle<Image> {
+        match topology {
+            Topology::Square => material.texture.clone(),
+            Topology::Hex => self.hex_texture.clone(),
+        }
+    }
+
+    /// Cover sprite of a tile, child of the tile sprite
+    pub fn cover_bundle(&self, topology: Topology, size: f32) -> SpriteBundle {
+        SpriteBundle {
+            sprite: Sprite {
+                custom_size: Some(topology.tile_sprite_size(size)),
+                color: self.covered_tile_material.color,
+                ..Default::default()
+            },
+            texture: self.tile_texture(&self.covered_tile_material, topology),
+            transform: Transform::from_xyz(0., 0., 2.),
+            ..Default::default()
+        }
+    }
+
+    /// Flag or question mark sprite, child of a tile cover
+    pub fn mark_bundle(&self, mark: TileMark, size: f32) -> SpriteBundle {
+        let material = match mark {
+            TileMark::Flag(_) => &self.flag_material,
+            TileMark::Question => &self.question_material,
+        };
+        SpriteBundle {
+            sprite: Sprite {
+                color: material.color,
+                custom_size: Some(Vec2::splat(size)),
+                ..Default::default()
+            },
+            texture: material.texture.clone(),
+            transform: Transform::from_xyz(0., 0., 1.),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+impl BoardAsset {
+    /// Assets with default handles, nothing being loaded
+    pub fn for_tests() -> Self {
+        Self {
+            label: String::new(),
+            board_material: Default::default(),
+            tile_material: Default::default(),
+            covered_tile_material: Default::default(),
+            bomb_counter_font: Default::default(),
+            bomb_counter_colors: Self::default_color(),
+            flag_material: Default::default(),
+            question_material: Default::default(),
+            bomb_material: Default::default(),
+            hex_texture: Default::default(),
+        }
+    }
+}
@@ -0,0 +1,448 @@
+//! Compact text codes of board layouts, to share a board with other players
+//!
+//! A code is the URL safe base64 of these byte sections, in order:
+//!
+//! 1. the version byte
+//! 2. the width and height, as little endian `u16`
+//! 3. the flags byte: start tile, hex tiles, wrapping edges, neighborhood,
+//!    several mines per tile and shaped board
+//! 4. the start tile, when flagged
+//! 5. the mines a tile can hold, when above one
+//! 6. the void bitmap of shaped boards
+//! 7. the bomb bitmap, in row-major order
+//! 8. the mines of every bomb tile, when a tile can hold several
+//! 9. a Fletcher-16 checksum of all the previous bytes
+use std::fmt;
+
+use super::map::{Map, MAX_MINES_PER_TILE};
+use super::tile::Tile;
+use super::topology::{Neighborhood, Topology};
+use crate::components::coordinates::Coordinates;
+
+/// Latest version of the codes written by `Map::encode`. Codes of classic
+/// boards keep the first version, which older builds can still read
+pub const CODE_VERSION: u8 = 2;
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+const HAS_START: u8 = 1;
+const HEX: u8 = 2;
+const WRAP: u8 = 4;
+// Two bits holding the index of the neighborhood in `Neighborhood::ALL`
+const NEIGHBORHOOD_SHIFT: u8 = 3;
+const NEIGHBORHOOD_MASK: u8 = 0b11;
+const MULTI_MINES: u8 = 32;
+const SHAPED: u8 = 64;
+// Flags known by each version, indexed by the version minus one
+const KNOWN_FLAGS: [u8; CODE_VERSION as usize] = [
+    HAS_START,
+    HAS_START | HEX | WRAP | NEIGHBORHOOD_MASK << NEIGHBORHOOD_SHIFT | MULTI_MINES | SHAPED,
+];
+
+/// Why a board code could not be read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CodeError {
+    InvalidCharacter(char),
+    /// Missing or extra bytes for the announced board size
+    InvalidLength,
+    Checksum,
+    UnsupportedVersion(u8),
+    /// Flags of a newer layout
+    UnknownFlags(u8),
+    EmptyBoard,
+    StartOutOfBounds,
+    /// Start tile holding a bomb, losing the game on the first click
+    StartOnBomb,
+    /// Bomb on a tile out of the board shape
+    BombInVoid,
+    /// Mines of a bomb tile out of `1..=max_mines`, or `max_mines` itself
+    /// out of `2..=MAX_MINES_PER_TILE`
+    InvalidMines(u8),
+}
+
+impl fmt::Display for CodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidCharacter(c) => write!(f, "invalid character {:?}", c),
+            Self::InvalidLength => write!(f, "truncated or too long code"),
+            Self::Checksum => write!(f, "checksum mismatch, the code is corrupted"),
+            Self::UnsupportedVersion(v) => write!(f, "unsupported code version {}", v),
+            Self::UnknownFlags(flags) => write!(f, "unknown board flags {:#04x}", flags),
+            Self::EmptyBoard => write!(f, "empty board"),
+            Self::StartOutOfBounds => write!(f, "start tile out of the board"),
+            Self::StartOnBomb => write!(f, "bomb on the start tile"),
+            Self::BombInVoid => write!(f, "bomb out of the board shape"),
+            Self::InvalidMines(mines) => write!(f, "invalid mines count {}", mines),
+        }
+    }
+}
+
+impl std::error::Error for CodeError {}
+
+impl Map {
+    /// Encodes the bomb layout, along with the tile to uncover first
+    pub fn encode(&self, start: Option<Coordinates>) -> String {
+        let mut flags = match self.topology() {
+            Topology::Square => 0,
+            Topology::Hex => HEX,
+        };
+        if self.wraps().0 {
+            flags |= WRAP;
+        }
+        let neighborhood = Neighborhood::ALL
+            .iter()
+            .position(|n| *n == self.neighborhood())
+            .unwrap_or_default() as u8;
+        flags |= neighborhood << NEIGHBORHOOD_SHIFT;
+        let multi_mines = self.max_mines() > 1;
+        if multi_mines {
+            flags |= MULTI_MINES;
+        }
+        let shaped = self.playable_count() < self.iter().count();
+        if shaped {
+            flags |= SHAPED;
+        }
+        if start.is_some() {
+            flags |= HAS_START;
+        }
+        // The oldest version knowing every flag
+        let version = (1..=CODE_VERSION)
+            .find(|v| flags & !KNOWN_FLAGS[*v as usize - 1] == 0)
+            .unwrap_or(CODE_VERSION);
+        let mut bytes = vec![version];
+        bytes.extend(self.width().to_le_bytes());
+        bytes.extend(self.height().to_le_bytes());
+        bytes.push(flags);
+        if let Some(start) = start {
+            bytes.extend(start.x.to_le_bytes());
+            bytes.extend(start.y.to_le_bytes());
+        }
+        if multi_mines {
+            bytes.push(self.max_mines());
+        }
+        let bitmap = |is_set: fn(&Tile) -> bool| {
+            let mut bitmap =
+                vec![0u8; (self.width() as usize * self.height() as usize).div_ceil(8)];
+            for (i, (_, tile)) in self.iter().enumerate() {
+                if is_set(tile) {
+                    bitmap[i / 8] |= 1 << (i % 8);
+                }
+            }
+            bitmap
+        };
+        if shaped {
+            bytes.extend(bitmap(|tile| *tile == Tile::Void));
+        }
+        bytes.extend(bitmap(Tile::is_bomb));
+        if multi_mines {
+            bytes.extend(self.iter().map(|(_, tile)| tile.mines()).filter(|m| *m > 0));
+        }
+        bytes.extend(fletcher16(&bytes).to_le_bytes());
+        to_base64(&bytes)
+    }
+
+    /// Reads a code written by `encode`, surrounding whitespace being ignored
+    pub fn decode(code: &str) -> Result<(Map, Option<Coordinates>), CodeError> {
+        let bytes = from_base64(code.trim())?;
+        let (content, checksum) = match bytes.len().checked_sub(2) {
+            Some(len) => bytes.split_at(len),
+            None => return Err(CodeError::InvalidLength),
+        };
+        if fletcher16(content).to_le_bytes() != checksum {
+            return Err(CodeError::Checksum);
+        }
+        let mut reader = Reader(content);
+        let known_flags = match reader.u8()? {
+            v @ 1..=CODE_VERSION => KNOWN_FLAGS[v as usize - 1],
+            v => return Err(CodeError::UnsupportedVersion(v)),
+        };
+        let (width, height) = (reader.u16()?, reader.u16()?);
+        if width == 0 || height == 0 {
+            return Err(CodeError::EmptyBoard);
+        }
+        let flags = reader.u8()?;
+        if flags & !known_flags != 0 {
+            return Err(CodeError::UnknownFlags(flags & !known_flags));
+        }
+        let topology = if flags & HEX != 0 {
+            Topology::Hex
+        } else {
+            Topology::Square
+        };
+        let start = if flags & HAS_START != 0 {
+            Some(Coordinates::new(reader.u16()?, reader.u16()?))
+        } else {
+            None
+        };
+        let max_mines = if flags & MULTI_MINES != 0 {
+            match reader.u8()? {
+                mines @ 2..=MAX_MINES_PER_TILE => mines,
+                mines => return Err(CodeError::InvalidMines(mines)),
+            }
+        } else {
+            1
+        };
+        let len = width as usize * height as usize;
+        let voids = if flags & SHAPED != 0 {
+            Some(reader.bytes(len.div_ceil(8))?)
+        } else {
+            None
+        };
+        let bitmap = reader.bytes(len.div_ceil(8))?;
+        let is_set = |bitmap: &[u8], c: Coordinates| {
+            let i = c.y as usize * width as usize + c.x as usize;
+            bitmap[i / 8] & (1 << (i % 8)) != 0
+        };
+        let is_void = |c: Coordinates| voids.is_some_and(|voids| is_set(voids, c));
+        let bombs: Vec<_> = (0..len)
+            .map(|i| Coordinates::new((i % width as usize) as u16, (i / width as usize) as u16))
+            .filter(|c| is_set(bitmap, *c))
+            .collect();
+        if bombs.iter().any(|c| is_void(*c)) {
+            return Err(CodeError::BombInVoid);
+        }
+        let neighborhood = (flags >> NEIGHBORHOOD_SHIFT) & NEIGHBORHOOD_MASK;
+        let mut map = Map::with_bombs(width, height, bombs.iter().copied())
+            .with_max_mines(max_mines)
+            .with_topology(topology)
+            .with_neighborhood(Neighborhood::ALL[neighborhood as usize])
+            .with_wrap(flags & WRAP != 0)
+            .with_mask(|c| !is_void(c));
+        if max_mines > 1 {
+            let mines = reader.bytes(bombs.len())?;
+            if let Some(mines) = mines.iter().find(|m| !(1..=max_mines).contains(*m)) {
+                return Err(CodeError::InvalidMines(*mines));
+            }
+            for (coords, mines) in bombs.into_iter().zip(mines) {
+                if *mines > 1 {
+                    map.set_mines_at(coords, *mines);
+                }
+            }
+        }
+        if !reader.0.is_empty() {
+            return Err(CodeError::InvalidLength);
+        }
+        if start.is_some_and(|s| !map.contains(s) || map.is_void_at(s)) {
+            return Err(CodeError::StartOutOfBounds);
+        }
+        if start.is_some_and(|s| map.is_bomb_at(s)) {
+            return Err(CodeError::StartOnBomb);
+        }
+        Ok((map, start))
+    }
+}
+
+// Consumes little endian values from the front of a buffer
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn u8(&mut self) -> Result<u8, CodeError> {
+        let (first, rest) = self.0.split_first().ok_or(CodeError::InvalidLength)?;
+        self.0 = rest;
+        Ok(*first)
+    }
+
+    fn u16(&mut self) -> Result<u16, CodeError> {
+        Ok(u16::from_le_bytes([self.u8()?, self.u8()?]))
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], CodeError> {
+        if self.0.len() < len {
+            return Err(CodeError::InvalidLength);
+        }
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(bytes)
+    }
+}
+
+fn fletcher16(bytes: &[u8]) -> u16 {
+    let (mut a, mut b) = (0u16, 0u16);
+    for byte in bytes {
+        a = (a + *byte as u16) % 255;
+        b = (b + a) % 255;
+    }
+    (b << 8) | a
+}
+
+fn to_base64(bytes: &[u8]) -> String {
+    let mut res = String::with_capacity((bytes.len() * 4).div_ceil(3));
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, byte)| n | (*byte as u32) << (16 - 8 * i));
+        for i in 0..=chunk.len() {
+            res.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+        }
+    }
+    res
+}
+
+fn from_base64(text: &str) -> Result<Vec<u8>, CodeError> {
+    let values = text
+        .chars()
+        .map(|c| {
+            ALPHABET
+                .iter()
+                .position(|a| *a as char == c)
+                .map(|v| v as u32)
+                .ok_or(CodeError::InvalidCharacter(c))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut bytes = Vec::with_capacity(values.len() * 3 / 4);
+    for chunk in values.chunks(4) {
+        if chunk.len() == 1 {
+            return Err(CodeError::InvalidLength);
+        }
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, v)| n | v << (18 - 6 * i));
+        for i in 0..chunk.len() - 1 {
+            bytes.push((n >> (16 - 8 * i)) as u8);
+        }
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resources::shape::BoardShape;
+
+    #[test]
+    fn round_trip() {
+        let bombs = [(0, 0), (4, 2), (2, 1)].map(Coordinates::from);
+        let map = Map::with_bombs(5, 3, bombs);
+        for start in [None, Some(Coordinates::new(4, 0))] {
+            let code = map.encode(start);
+            let (decoded, decoded_start) = Map::decode(&code).unwrap();
+            assert_eq!(decoded_start, start);
+            assert_eq!(decoded.width(), 5);
+            assert_eq!(decoded.height(), 3);
+            assert!(decoded.iter().eq(map.iter()));
+        }
+    }
+
+    #[test]
+    fn keeps_the_topology() {
+        let map = Map::with_bombs(4, 4, [Coordinates::new(0, 0)])
+            .with_topology(Topology::Hex)
+            .with_wrap(true);
+        let (decoded, _) = Map::decode(&map.encode(None)).unwrap();
+        assert_eq!(decoded.topology(), Topology::Hex);
+        assert_eq!(decoded.wraps(), (true, true));
+        assert!(decoded.iter().eq(map.iter()));
+        let map =
+            Map::with_bombs(4, 4, [Coordinates::new(0, 0)]).with_neighborhood(Neighborhood::Knight);
+        let (decoded, _) = Map::decode(&map.encode(None)).unwrap();
+        assert_eq!(decoded.neighborhood(), Neighborhood::Knight);
+        assert!(decoded.iter().eq(map.iter()));
+    }
+
+    #[test]
+    fn keeps_the_shape() {
+        let map = Map::with_bombs(9, 9, [Coordinates::new(4, 4)]).with_shape(BoardShape::Heart);
+        let (decoded, _) = Map::decode(&map.encode(None)).unwrap();
+        assert_eq!(decoded.playable_count(), map.playable_count());
+        assert!(decoded.iter().eq(map.iter()));
+        // A plain rectangle needs no void bitmap
+        let rectangle = Map::with_bombs(9, 9, [Coordinates::new(4, 4)]);
+        assert!(map.encode(None).len() > rectangle.encode(None).len());
+    }
+
+    #[test]
+    fn keeps_multi_mine_tiles() {
+        let mut map = Map::empty(5, 3).with_max_mines(3);
+        map.set_mines_at(Coordinates::new(0, 0), 3);
+        map.set_mines_at(Coordinates::new(4, 2), 1);
+        let (decoded, _) = Map::decode(&map.encode(None)).unwrap();
+        assert_eq!(decoded.max_mines(), 3);
+        assert_eq!(decoded.bomb_count(), 4);
+        assert!(decoded.iter().eq(map.iter()));
+    }
+
+    #[test]
+    fn rejects_invalid_mine_counts() {
+        let mut map = Map::empty(5, 3).with_max_mines(3);
+        map.set_mines_at(Coordinates::new(0, 0), 3);
+        let mut bytes = from_base64(&map.encode(None)).unwrap();
+        bytes.truncate(bytes.len() - 2);
+        // The mines of the only bomb tile come last
+        for mines in [0, 4] {
+            *bytes.last_mut().unwrap() = mines;
+            let mut code = bytes.clone();
+            code.extend(fletcher16(&code).to_le_bytes());
+            assert_eq!(
+                Map::decode(&to_base64(&code)).err(),
+                Some(CodeError::InvalidMines(mines))
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_corrupted_codes() {
+        let map = Map::with_bombs(9, 9, [Coordinates::new(3, 3)]);
+        let code = map.encode(None);
+        let mut corrupted: Vec<char> = code.chars().collect();
+        corrupted[8] = if corrupted[8] == 'A' { 'B' } else { 'A' };
+        let corrupted: String = corrupted.into_iter().collect();
+        assert_eq!(Map::decode(&corrupted).err(), Some(CodeError::Checksum));
+        assert!(Map::decode(&code[..code.len() - 4]).is_err());
+        assert_eq!(
+            Map::decode("not a code").err(),
+            Some(CodeError::InvalidCharacter(' '))
+        );
+    }
+
+    #[test]
+    fn rejects_other_versions() {
+        let mut bytes = vec![CODE_VERSION + 1, 1, 0, 1, 0, 0, 0];
+        bytes.extend(fletcher16(&bytes).to_le_bytes());
+        assert_eq!(
+            Map::decode(&to_base64(&bytes)).err(),
+            Some(CodeError::UnsupportedVersion(CODE_VERSION + 1))
+        );
+    }
+
+    #[test]
+    fn variants_need_the_latest_version() {
+        let classic = Map::with_bombs(3, 3, [Coordinates::new(1, 1)]);
+        let bytes = from_base64(&classic.encode(Some(Coordinates::new(0, 0)))).unwrap();
+        assert_eq!(bytes[0], 1);
+        let hex = classic.clone().with_topology(Topology::Hex);
+        let mut bytes = from_base64(&hex.encode(None)).unwrap();
+        assert_eq!(bytes[0], CODE_VERSION);
+        // The first version had no hex tiles
+        bytes.truncate(bytes.len() - 2);
+        bytes[0] = 1;
+        bytes.extend(fletcher16(&bytes).to_le_bytes());
+        assert_eq!(
+            Map::decode(&to_base64(&bytes)).err(),
+            Some(CodeError::UnknownFlags(HEX))
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_flags() {
+        let map = Map::with_bombs(3, 3, [Coordinates::new(1, 1)]);
+        let mut bytes = from_base64(&map.encode(None)).unwrap();
+        bytes.truncate(bytes.len() - 2);
+        bytes[5] |= 128;
+        bytes.extend(fletcher16(&bytes).to_le_bytes());
+        assert_eq!(
+            Map::decode(&to_base64(&bytes)).err(),
+            Some(CodeError::UnknownFlags(128))
+        );
+    }
+
+    #[test]
+    fn rejects_a_bomb_on_the_start() {
+        let map = Map::with_bombs(3, 3, [Coordinates::new(1, 1)]);
+        assert!(Map::decode(&map.encode(Some(Coordinates::new(0, 0)))).is_ok());
+        assert_eq!(
+            Map::decode(&map.encode(Some(Coordinates::new(1, 1)))).err(),
+            Some(CodeError::StartOnBomb)
+        );
+    }
+}
@@ -3,6 +3,10 @@
 use bevy::prelude::{Vec3, Resource};
 use serde::{ Serialize, Deserialize };
 
+use super::map::Map;
+use super::shape::BoardShape;
+use super::topology::{Neighborhood, Topology};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TileSize {
     /// Fixed tile size
@@ -38,6 +42,7 @@ impl Default for BoardPosition {
 
 // Impl Resource trait which is needed for app.insert_resource
 #[derive(Debug, Clone, Serialize, Deserialize, Resource)]
+#[serde(default)]
 pub struct BoardOptions {
     /// Tile map size
     pub map_size: (u16, u16),
@@ -51,6 +56,46 @@ pub struct BoardOptions {
     pub tile_padding: f32,
     /// Does the board generate a safe place to start
     pub safe_place: bool,
+    /// Seed of the bombs placement, random when `None`
+    pub seed: Option<u64>,
+    /// Bombs the player can hit before losing, `1` being the classic rules
+    pub lives: u8,
+    /// Does marking cycle through a question mark after the flag
+    pub marks: bool,
+    /// Board code to play instead of a random layout, see `Map::decode`
+    pub layout: Option<String>,
+    /// Shape of the tiles of a random layout, codes carrying their own
+    pub topology: Topology,
+    /// Do the neighbors of a random layout wrap across the edges
+    pub wrap: bool,
+    /// Neighbors of the square tiles of a random layout
+    pub neighborhood: Neighborhood,
+    /// Mines a tile of a random layout can hold, `boom_count` counting them all
+    pub max_mines: u8,
+    /// Playable area of a random layout
+    pub shape: BoardShape,
+}
+
+impl BoardOptions {
+    /// Whether random boards are plain rectangles of square tiles, the only
+    /// ones whose times compare in the statistics
+    pub fn is_classic_grid(&self) -> bool {
+        self.topology == Topology::Square
+            && !self.wrap
+            && self.neighborhood == Neighborhood::Moore
+            && self.max_mines <= 1
+            && self.shape == BoardShape::Rectangle
+    }
+
+    /// Map without bombs of the size, shape and rules of a random layout
+    pub fn empty_map(&self) -> Map {
+        Map::empty(self.map_size.0, self.map_size.1)
+            .with_topology(self.topology)
+            .with_neighborhood(self.neighborhood)
+            .with_wrap(self.wrap)
+            .with_max_mines(self.max_mines)
+            .with_shape(self.shape)
+    }
 }
 
 impl Default for BoardOptions {
@@ -61,7 +106,16 @@ impl Default for BoardOptions {
             position: Default::default(), 
             tile_size: Default::default(), 
             tile_padding: 0., 
-            safe_place: false 
+            safe_place: false,
+            seed: None,
+            lives: 1,
+            marks: false,
+            layout: None,
+            topology: Default::default(),
+            wrap: false,
+            neighborhood: Default::default(),
+            max_mines: 1,
+            shape: Default::default(),
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+
+use super::board_options::BoardOptions;
+
+/// Classic board presets
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Difficulty {
+    /// 9x9 board with 10 bombs
+    #[default]
+    Beginner,
+    /// 16x16 board with 40 bombs
+    Intermediate,
+    /// 30x16 board with 99 bombs
+    Expert,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Self::Beginner, Self::Intermediate, Self::Expert];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Beginner => "Beginner",
+            Self::Intermediate => "Intermediate",
+            Self::Expert => "Expert",
+        }
+    }
+
+    /// Tile map size
+    pub fn map_size(&self) -> (u16, u16) {
+        match self {
+            Self::Beginner => (9, 9),
+            Self::Intermediate => (16, 16),
+            Self::Expert => (30, 16),
+        }
+    }
+
+    /// Bombs count
+    pub fn boom_count(&self) -> u16 {
+        match self {
+            Self::Beginner => 10,
+            Self::Intermediate => 40,
+            Self::Expert => 99,
+        }
+    }
+
+    /// Board options matching the preset
+    pub fn board_options(&self) -> BoardOptions {
+        BoardOptions {
+            map_size: self.map_size(),
+            boom_count: self.boom_count(),
+            tile_padding: 3.0,
+            safe_place: true,
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use crate::components::coordinates::Coordinates;
+
+use super::board::TileMark;
+
+/// Player action which can be undone
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    /// Tiles uncovered by a single click, flood fill and chords included,
+    /// along with the question mark they may have had
+    Reveal(Vec<(Coordinates, Option<TileMark>)>),
+    /// Right click cycling the mark of a covered tile
+    Mark {
+        coords: Coordinates,
+        from: Option<TileMark>,
+        to: Option<TileMark>,
+    },
+}
+
+/// Actions played on the board, the most recent last
+#[derive(Debug, Default)]
+pub struct History {
+    actions: Vec<Action>,
+}
+
+impl History {
+    /// Starts a new reveal, the tiles uncovered until the next action being
+    /// undone together
+    pub fn start_reveal(&mut self) {
+        self.actions.push(Action::Reveal(Vec::new()));
+    }
+
+    /// Adds an uncovered tile to the latest reveal, marks put while a flood
+    /// fill spreads being skipped. Tiles uncovered before any reveal was
+    /// started, like the safe start opening, are not recorded and can't be
+    /// covered again
+    pub fn record_reveal(&mut self, coords: Coordinates, mark: Option<TileMark>) {
+        let reveal = self
+            .actions
+            .iter_mut()
+            .rev()
+            .find_map(|action| match action {
+                Action::Reveal(tiles) => Some(tiles),
+                Action::Mark { .. } => None,
+            });
+        if let Some(tiles) = reveal {
+            tiles.push((coords, mark));
+        }
+    }
+
+    pub fn record_mark(
+        &mut self,
+        coords: Coordinates,
+        from: Option<TileMark>,
+        to: Option<TileMark>,
+    ) {
+        self.actions.push(Action::Mark { coords, from, to });
+    }
+
+    /// Removes the last action, skipping reveals which uncovered nothing
+    pub fn pop(&mut self) -> Option<Action> {
+        while let Some(action) = self.actions.pop() {
+            if !matches!(&action, Action::Reveal(tiles) if tiles.is_empty()) {
+                return Some(action);
+            }
+        }
+        None
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.actions
+            .iter()
+            .all(|a| matches!(a, Action::Reveal(tiles) if tiles.is_empty()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pop_skips_empty_reveals() {
+        let mut history = History::default();
+        history.start_reveal();
+        history.record_reveal((0, 0).into(), None);
+        history.record_reveal((1, 0).into(), Some(TileMark::Question));
+        history.record_mark((2, 2).into(), None, Some(TileMark::Flag(1)));
+        history.start_reveal();
+        assert!(!history.is_empty());
+        assert_eq!(
+            history.pop(),
+            Some(Action::Mark {
+                coords: (2, 2).into(),
+                from: None,
+                to: Some(TileMark::Flag(1))
+            })
+        );
+        assert_eq!(
+            history.pop(),
+            Some(Action::Reveal(vec![
+                ((0, 0).into(), None),
+                ((1, 0).into(), Some(TileMark::Question))
+            ]))
+        );
+        assert!(history.is_empty());
+        assert_eq!(history.pop(), None);
+    }
+
+    #[test]
+    fn the_safe_start_is_not_recorded() {
+        let mut history = History::default();
+        // Uncovered by the board itself, before any click
+        history.record_reveal((0, 0).into(), None);
+        assert!(history.is_empty());
+        history.start_reveal();
+        history.record_reveal((4, 4).into(), None);
+        // A flag put while the flood fill spreads
+        history.record_mark((2, 2).into(), None, Some(TileMark::Flag(1)));
+        history.record_reveal((4, 5).into(), None);
+        history.pop();
+        assert_eq!(
+            history.pop(),
+            Some(Action::Reveal(vec![
+                ((4, 4).into(), None),
+                ((4, 5).into(), None)
+            ]))
+        );
+        assert!(history.is_empty());
+    }
+}
@@ -1,123 +1,419 @@
-use std::ops::{Deref, DerefMut};
-use rand::{thread_rng, Rng};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 
+use super::shape::BoardShape;
 use super::tile::Tile;
+use super::topology::{Neighborhood, Topology};
 use crate::components::coordinates::Coordinates;
 
-// Delta coordinates for all 8 square neighbors
-const SQUARE_COORDINATES: [(i8, i8); 8] = [
-    (-1, -1),
-    (0, -1),
-    (1, -1),
-    (-1, 0),
-    (1, 0),
-    (-1, 1),
-    (0, 1),
-    (1, 1),
-];
-
-#[derive(Debug)]
+/// Most mines a tile can hold, keeping them a single digit and the sum of
+/// 24 neighbors in a `u8`
+pub const MAX_MINES_PER_TILE: u8 = 9;
+
+/// Tile map stored as a single row-major buffer, `y = 0` being the bottom row
+#[derive(Debug, Clone)]
 pub struct Map {
     bomb_count: u16,
     height: u16,
     width: u16,
-    map: Vec<Vec<Tile>>
+    tiles: Vec<Tile>,
+    topology: Topology,
+    /// Neighbors of square tiles
+    neighborhood: Neighborhood,
+    /// Do neighbors wrap across the edges
+    wrap: bool,
+    /// Mines a single tile can hold
+    max_mines: u8,
 }
 
 impl Map {
-    /// Generates a new map
+    /// Generates a new map of square tiles
     pub fn empty(width: u16, height: u16) -> Self {
-        let map = (0..height)
-            .into_iter()
-            .map(|_| (0..width).into_iter().map(|_| Tile::Empty).collect())
+        let tiles = (0..width as usize * height as usize)
+            .map(|_| Tile::Empty)
             .collect();
 
         Self {
             bomb_count: 0,
             height,
             width,
-            map,
+            tiles,
+            topology: Topology::default(),
+            neighborhood: Neighborhood::default(),
+            wrap: false,
+            max_mines: 1,
+        }
+    }
+
+    /// Map of the same size, shape and rules without any bomb
+    pub fn without_bombs(&self) -> Self {
+        let tiles = self
+            .tiles
+            .iter()
+            .map(|tile| match tile {
+                Tile::Void => Tile::Void,
+                _ => Tile::Empty,
+            })
+            .collect();
+        Self {
+            bomb_count: 0,
+            tiles,
+            ..*self
         }
     }
 
+    /// Cuts the map to the given shape, see `with_mask`
+    pub fn with_shape(self, shape: BoardShape) -> Self {
+        let size = (self.width, self.height);
+        self.with_mask(|coords| shape.contains(coords, size))
+    }
+
+    /// Turns the tiles out of the mask into void, their mines being dropped
+    /// and the numbers recomputed. Void tiles stay void
+    pub fn with_mask(mut self, is_playable: impl Fn(Coordinates) -> bool) -> Self {
+        for (i, coords) in self.iter_coords().enumerate().collect::<Vec<_>>() {
+            if !is_playable(coords) {
+                self.bomb_count -= self.tiles[i].mines() as u16;
+                self.tiles[i] = Tile::Void;
+            }
+        }
+        self.place_bomb_neighbors();
+        self
+    }
+
+    /// Changes the tile shape, the numbers being recomputed
+    pub fn with_topology(mut self, topology: Topology) -> Self {
+        self.topology = topology;
+        self.place_bomb_neighbors();
+        self
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// Changes which square tiles are neighbors, the numbers being recomputed
+    pub fn with_neighborhood(mut self, neighborhood: Neighborhood) -> Self {
+        self.neighborhood = neighborhood;
+        self.place_bomb_neighbors();
+        self
+    }
+
+    pub fn neighborhood(&self) -> Neighborhood {
+        self.neighborhood
+    }
+
+    /// Makes neighbors wrap across the edges, the numbers being recomputed
+    pub fn with_wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self.place_bomb_neighbors();
+        self
+    }
+
+    /// Lets `set_bombs` put up to `max_mines` mines on a tile, between one
+    /// and `MAX_MINES_PER_TILE`
+    pub fn with_max_mines(mut self, max_mines: u8) -> Self {
+        self.max_mines = max_mines.clamp(1, MAX_MINES_PER_TILE);
+        self
+    }
+
+    pub fn max_mines(&self) -> u8 {
+        self.max_mines
+    }
+
+    /// Whether neighbors wrap across the left and right edges, then across
+    /// the bottom and top edges. Hex rows only wrap in even numbers, odd rows
+    /// being shifted
+    pub fn wraps(&self) -> (bool, bool) {
+        let rows = self.topology == Topology::Square || self.height.is_multiple_of(2);
+        (self.wrap, self.wrap && rows)
+    }
+
+    /// Number of columns
     pub fn width(&self) -> u16 {
         self.width
     }
 
+    /// Number of rows
     pub fn height(&self) -> u16 {
         self.height
     }
 
+    /// Number of mines on the map, a tile holding several counting them all
     pub fn bomb_count(&self) -> u16 {
         self.bomb_count
     }
-    
-    pub fn safe_square_at(&self, coordinates: Coordinates) -> impl Iterator<Item = Coordinates> {
-        SQUARE_COORDINATES
+
+    /// Number of tiles which are not void
+    pub fn playable_count(&self) -> usize {
+        self.tiles
             .iter()
-            .copied()
-            .map(move |tuple| coordinates + tuple)    
+            .filter(|tile| **tile != Tile::Void)
+            .count()
     }
 
-    pub fn is_bomb_at(&self, coordinates: Coordinates) -> bool {
-        if coordinates.x >= self.width || coordinates.y >= self.height {
-            return false;
+    /// Whether the tile is a hole in the board shape, `false` when out of the map
+    pub fn is_void_at(&self, coordinates: Coordinates) -> bool {
+        self.get(coordinates) == Some(&Tile::Void)
+    }
+
+    /// Whether the coordinates lie inside the map
+    pub fn contains(&self, coordinates: Coordinates) -> bool {
+        coordinates.x < self.width && coordinates.y < self.height
+    }
+
+    /// Retrieves the tile at the given coordinates
+    pub fn get(&self, coordinates: Coordinates) -> Option<&Tile> {
+        self.index(coordinates).map(|i| &self.tiles[i])
+    }
+
+    /// Iterates over all the coordinates of the map, row by row from the bottom
+    pub fn iter_coords(&self) -> impl Iterator<Item = Coordinates> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| Coordinates { x, y }))
+    }
+
+    /// Iterates over every tile along with its coordinates
+    pub fn iter(&self) -> impl Iterator<Item = (Coordinates, &Tile)> {
+        self.iter_coords().zip(self.tiles.iter())
+    }
+
+    /// Iterates over the tiles of the row `y`
+    pub fn row(&self, y: u16) -> impl Iterator<Item = &Tile> {
+        let range = if y < self.height {
+            let start = y as usize * self.width as usize;
+            start..start + self.width as usize
+        } else {
+            0..0
         };
-        self.map[coordinates.y as usize][coordinates.x as usize].is_bomb()
+        self.tiles[range].iter()
     }
 
+    /// Iterates over the rows of the map, from the bottom
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[Tile]> {
+        // `chunks` panics on a zero size
+        self.tiles.chunks(self.width.max(1) as usize)
+    }
+
+    /// Iterates over the tiles of the column `x`
+    pub fn column(&self, x: u16) -> impl Iterator<Item = &Tile> {
+        let (skip, height) = if x < self.width {
+            (x as usize, self.height as usize)
+        } else {
+            (0, 0)
+        };
+        self.tiles
+            .iter()
+            .skip(skip)
+            .step_by(self.width.max(1) as usize)
+            .take(height)
+    }
+
+    /// Iterates over the in-bounds neighbors of the given coordinates, each
+    /// one once even when wrapping on a small board. Void tiles have no
+    /// neighbors and are no one's neighbor
+    pub fn neighbors(&self, coordinates: Coordinates) -> impl Iterator<Item = Coordinates> + '_ {
+        let deltas = if self.contains(coordinates) && !self.is_void_at(coordinates) {
+            self.topology
+                .neighbor_deltas(coordinates, self.neighborhood)
+        } else {
+            &[]
+        };
+        // Wrapping edges closer than the widest neighborhood, 5 tiles
+        // across, reach some tiles twice
+        let (wrap_x, wrap_y) = self.wraps();
+        let dedupe = (wrap_x && self.width < 5) || (wrap_y && self.height < 5);
+        deltas.iter().enumerate().filter_map(move |(i, delta)| {
+            let coor = self.wrapped_add(coordinates, *delta)?;
+            if coor == coordinates || self.is_void_at(coor) {
+                return None;
+            }
+            let seen = dedupe
+                && deltas[..i]
+                    .iter()
+                    .any(|d| self.wrapped_add(coordinates, *d) == Some(coor));
+            (!seen).then_some(coor)
+        })
+    }
+
+    /// Sets the mines of a tile, at most `max_mines`, the numbers being
+    /// recomputed. Void tiles can't hold any
+    pub fn set_mines_at(&mut self, coordinates: Coordinates, mines: u8) {
+        let mines = mines.min(self.max_mines);
+        let Some(tile) = self.get_mut(coordinates).filter(|t| **t != Tile::Void) else {
+            return;
+        };
+        let previous = tile.mines();
+        if previous == mines {
+            return;
+        }
+        *tile = if mines > 0 {
+            Tile::Bomb(mines)
+        } else {
+            Tile::Empty
+        };
+        self.bomb_count = self.bomb_count + mines as u16 - previous as u16;
+        self.place_bomb_neighbors();
+    }
+
+    /// Is there a bomb at the given coordinates, `false` when out of the map
+    pub fn is_bomb_at(&self, coordinates: Coordinates) -> bool {
+        self.get(coordinates).is_some_and(Tile::is_bomb)
+    }
+
+    /// Mines held by the tile at the given coordinates, `0` when out of the map
+    pub fn mines_at(&self, coordinates: Coordinates) -> u8 {
+        self.get(coordinates).map_or(0, Tile::mines)
+    }
+
+    /// Sums the mines around the given coordinates, `0` for a bomb tile
     pub fn bomb_count_at(&self, coordinates: Coordinates) -> u8 {
         if self.is_bomb_at(coordinates) {
             return 0;
         }
-        let res = self
-            .safe_square_at(coordinates)
-            .filter(|coor| self.is_bomb_at(*coor))
-            .count();
-        res as u8
+        self.neighbors(coordinates)
+            .fold(0u8, |sum, coor| sum.saturating_add(self.mines_at(coor)))
     }
 
-    pub fn set_bombs(&mut self, boom_count: u16) {
+    /// Randomly places `boom_count` mines and computes the neighbor counts.
+    /// The generator is pinned, so the same seed gives the same map on every
+    /// platform and version. A tile picked again gets one more mine until it
+    /// holds `max_mines`. No more mines are placed than the tiles which are
+    /// not void can hold
+    pub fn set_bombs(&mut self, boom_count: u16, seed: u64) {
+        let capacity = self.playable_count() * self.max_mines as usize;
+        let boom_count = boom_count.min(capacity.try_into().unwrap_or(u16::MAX));
         self.bomb_count = boom_count;
+        let max_mines = self.max_mines;
         let mut remaining_bombs = boom_count;
-        let mut rng = thread_rng();
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
         while remaining_bombs > 0 {
-            let (x, y) = (
-                rng.gen_range(0..self.width) as usize,
-                rng.gen_range(0..self.height) as usize,
-            );
-            if let Tile::Empty = self[y][x] {
-                self[y][x] = Tile::Bomb;
-                remaining_bombs -= 1;
+            let coor = Coordinates {
+                x: rng.gen_range(0..self.width),
+                y: rng.gen_range(0..self.height),
+            };
+            let tile = self.get_mut(coor).expect("bomb placed out of the map");
+            match tile {
+                Tile::Empty => *tile = Tile::Bomb(1),
+                Tile::Bomb(mines) if *mines < max_mines => *mines += 1,
+                _ => continue,
             }
+            remaining_bombs -= 1;
         }
-        // Palce bomb neighbors
-        for y in 0..self.height {
-            for x in 0..self.width {
-                let coor = Coordinates { x, y };
-                if self.is_bomb_at(coor) {
-                    continue;
+        self.place_bomb_neighbors();
+    }
+
+    /// Generates a map with bombs at the given coordinates, out of bounds
+    /// and duplicated ones being ignored
+    pub fn with_bombs(
+        width: u16,
+        height: u16,
+        bombs: impl IntoIterator<Item = Coordinates>,
+    ) -> Self {
+        let mut map = Self::empty(width, height);
+        for coor in bombs {
+            if let Some(tile) = map.get_mut(coor) {
+                if !tile.is_bomb() {
+                    *tile = Tile::Bomb(1);
+                    map.bomb_count += 1;
                 }
-                let num = self.bomb_count_at(coor);
-                if num == 0 {
-                    continue;
+            }
+        }
+        map.place_bomb_neighbors();
+        map
+    }
+
+    /// Board 3BV: minimum number of left clicks needed to uncover every safe
+    /// tile, each opening counting once along with the numbers not
+    /// bordering any opening
+    pub fn three_bv(&self) -> u32 {
+        let mut marked = vec![false; self.tiles.len()];
+        let mut count = 0;
+        // Openings, flood filled with their bordering numbers
+        for coor in self.iter_coords() {
+            let i = self.index(coor).unwrap();
+            if marked[i] || self.tiles[i] != Tile::Empty {
+                continue;
+            }
+            count += 1;
+            marked[i] = true;
+            let mut stack = vec![coor];
+            while let Some(coor) = stack.pop() {
+                for neighbor in self.neighbors(coor) {
+                    let j = self.index(neighbor).unwrap();
+                    if marked[j] {
+                        continue;
+                    }
+                    marked[j] = true;
+                    if self.tiles[j] == Tile::Empty {
+                        stack.push(neighbor);
+                    }
                 }
-                // Modify coordinates value
-                let tile = &mut self[y as usize][x as usize];
-                *tile = Tile::BombNeighbor(num);
+            }
+        }
+        // Isolated numbers
+        count
+            + self
+                .tiles
+                .iter()
+                .zip(marked)
+                .filter(|(tile, marked)| !marked && matches!(tile, Tile::BombNeighbor(_)))
+                .count() as u32
+    }
+
+    // Recomputes every safe tile from its neighbors
+    fn place_bomb_neighbors(&mut self) {
+        for coor in self.iter_coords().collect::<Vec<_>>() {
+            if self.is_bomb_at(coor) || self.is_void_at(coor) {
+                continue;
+            }
+            let tile = match self.bomb_count_at(coor) {
+                0 => Tile::Empty,
+                num => Tile::BombNeighbor(num),
+            };
+            // Modify coordinates value
+            if let Some(t) = self.get_mut(coor) {
+                *t = tile;
             }
         }
     }
 
+    // Offsets in-bounds coordinates, going across the edges which wrap
+    fn wrapped_add(&self, coordinates: Coordinates, (x, y): (i8, i8)) -> Option<Coordinates> {
+        let (wrap_x, wrap_y) = self.wraps();
+        let wrap = |value: u16, delta: i8, len: u16, wraps: bool| {
+            let value = value as i32 + delta as i32;
+            if wraps {
+                Some(value.rem_euclid(len as i32) as u16)
+            } else {
+                u16::try_from(value).ok().filter(|v| *v < len)
+            }
+        };
+        Some(Coordinates {
+            x: wrap(coordinates.x, x, self.width, wrap_x)?,
+            y: wrap(coordinates.y, y, self.height, wrap_y)?,
+        })
+    }
+
+    fn index(&self, coordinates: Coordinates) -> Option<usize> {
+        self.contains(coordinates)
+            .then(|| coordinates.y as usize * self.width as usize + coordinates.x as usize)
+    }
+
+    // Kept private so `bomb_count` always matches the map contents
+    fn get_mut(&mut self, coordinates: Coordinates) -> Option<&mut Tile> {
+        self.index(coordinates).map(move |i| &mut self.tiles[i])
+    }
+
     #[cfg(feature = "debug")]
     pub fn console_output(&self) -> String {
         let mut buffer = format!(
             "Map ({}, {}) with {} bombs: \n",
             self.width, self.height, self.bomb_count
         );
-        let line: String = (0..=(self.width + 1)).into_iter().map(|_| '-').collect();
+        let line: String = (0..=(self.width + 1)).map(|_| '-').collect();
         buffer = format!("{}{}\n", buffer, line);
-        for line in self.iter().rev() {
+        for line in self.rows().rev() {
             buffer = format!("{}|", buffer);
             for tile in line.iter() {
                 buffer = format!("{}{}", buffer, tile.console_output())
@@ -128,16 +424,192 @@ impl Map {
     }
 }
 
-impl Deref for Map {
-    type Target = Vec<Vec<Tile>>;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    fn deref(&self) -> &Self::Target {
-        &self.map
+    fn sorted_neighbors(map: &Map, x: u16, y: u16) -> Vec<(u16, u16)> {
+        let mut res: Vec<_> = map
+            .neighbors(Coordinates { x, y })
+            .map(|c| (c.x, c.y))
+            .collect();
+        res.sort();
+        res
+    }
+
+    #[test]
+    fn neighbors_of_corners() {
+        let map = Map::empty(4, 3);
+        assert_eq!(sorted_neighbors(&map, 0, 0), vec![(0, 1), (1, 0), (1, 1)]);
+        assert_eq!(sorted_neighbors(&map, 3, 0), vec![(2, 0), (2, 1), (3, 1)]);
+        assert_eq!(sorted_neighbors(&map, 0, 2), vec![(0, 1), (1, 1), (1, 2)]);
+        assert_eq!(sorted_neighbors(&map, 3, 2), vec![(2, 1), (2, 2), (3, 1)]);
+    }
+
+    #[test]
+    fn neighbors_of_edges() {
+        let map = Map::empty(4, 3);
+        assert_eq!(
+            sorted_neighbors(&map, 1, 0),
+            vec![(0, 0), (0, 1), (1, 1), (2, 0), (2, 1)]
+        );
+        assert_eq!(
+            sorted_neighbors(&map, 3, 1),
+            vec![(2, 0), (2, 1), (2, 2), (3, 0), (3, 2)]
+        );
+    }
+
+    #[test]
+    fn neighbors_of_center() {
+        let map = Map::empty(3, 3);
+        assert_eq!(map.neighbors(Coordinates { x: 1, y: 1 }).count(), 8);
+    }
+
+    #[test]
+    fn hex_neighbors() {
+        let map = Map::empty(4, 4).with_topology(Topology::Hex);
+        assert_eq!(map.neighbors(Coordinates { x: 1, y: 1 }).count(), 6);
+        // Odd rows are shifted right
+        assert_eq!(
+            sorted_neighbors(&map, 1, 1),
+            vec![(0, 1), (1, 0), (1, 2), (2, 0), (2, 1), (2, 2)]
+        );
+        assert_eq!(
+            sorted_neighbors(&map, 1, 2),
+            vec![(0, 1), (0, 2), (0, 3), (1, 1), (1, 3), (2, 2)]
+        );
+        let map = Map::with_bombs(3, 3, [(0, 0).into()]).with_topology(Topology::Hex);
+        assert_eq!(map.get((1, 1).into()), Some(&Tile::Empty));
+        assert_eq!(map.get((0, 1).into()), Some(&Tile::BombNeighbor(1)));
+    }
+
+    #[test]
+    fn wrapped_neighbors() {
+        let map = Map::empty(4, 3).with_wrap(true);
+        assert_eq!(
+            sorted_neighbors(&map, 0, 0),
+            vec![
+                (0, 1),
+                (0, 2),
+                (1, 0),
+                (1, 1),
+                (1, 2),
+                (3, 0),
+                (3, 1),
+                (3, 2)
+            ]
+        );
+        // Both sides of a 2 wide board are the same tile
+        let map = Map::empty(2, 3).with_wrap(true);
+        assert_eq!(
+            sorted_neighbors(&map, 0, 0),
+            vec![(0, 1), (0, 2), (1, 0), (1, 1), (1, 2)]
+        );
+        let map = Map::with_bombs(4, 4, [(3, 3).into()]).with_wrap(true);
+        assert_eq!(map.get((0, 0).into()), Some(&Tile::BombNeighbor(1)));
+        // Odd hex rows can't wrap
+        let map = Map::empty(4, 3)
+            .with_topology(Topology::Hex)
+            .with_wrap(true);
+        assert_eq!(map.wraps(), (true, false));
+    }
+
+    #[test]
+    fn other_neighborhoods() {
+        let map = Map::empty(5, 5).with_neighborhood(Neighborhood::Cross);
+        assert_eq!(
+            sorted_neighbors(&map, 2, 2),
+            vec![(1, 2), (2, 1), (2, 3), (3, 2)]
+        );
+        let map = Map::empty(5, 5).with_neighborhood(Neighborhood::Knight);
+        assert_eq!(sorted_neighbors(&map, 0, 0), vec![(1, 2), (2, 1)]);
+        let map = Map::empty(5, 5).with_neighborhood(Neighborhood::Radius2);
+        assert_eq!(map.neighbors(Coordinates { x: 2, y: 2 }).count(), 24);
+        // A knight's move away, the adjacent tiles are not counted
+        let map = Map::with_bombs(3, 3, [(0, 0).into()]).with_neighborhood(Neighborhood::Knight);
+        assert_eq!(map.get((1, 1).into()), Some(&Tile::Empty));
+        assert_eq!(map.get((2, 1).into()), Some(&Tile::BombNeighbor(1)));
+        assert_eq!(map.without_bombs().neighborhood(), Neighborhood::Knight);
     }
-}
 
-impl DerefMut for Map {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.map
+    #[test]
+    fn neighbors_out_of_bounds() {
+        let map = Map::empty(3, 3);
+        assert_eq!(map.neighbors(Coordinates { x: 10, y: 10 }).count(), 0);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn with_bombs_places_numbers() {
+        let map = Map::with_bombs(3, 3, [(0, 0).into(), (0, 0).into(), (5, 5).into()]);
+        assert_eq!(map.bomb_count(), 1);
+        assert_eq!(map.get((0, 0).into()), Some(&Tile::Bomb(1)));
+        assert_eq!(map.get((1, 1).into()), Some(&Tile::BombNeighbor(1)));
+        assert_eq!(map.get((2, 2).into()), Some(&Tile::Empty));
+    }
+
+    #[test]
+    fn multi_mine_tiles() {
+        let mut map = Map::empty(3, 3).with_max_mines(3);
+        map.set_mines_at((0, 0).into(), 2);
+        map.set_mines_at((2, 2).into(), 5);
+        assert_eq!(map.get((2, 2).into()), Some(&Tile::Bomb(3)));
+        assert_eq!(map.bomb_count(), 5);
+        // Numbers sum the mines of their neighbors
+        assert_eq!(map.get((1, 1).into()), Some(&Tile::BombNeighbor(5)));
+        assert_eq!(map.get((1, 0).into()), Some(&Tile::BombNeighbor(2)));
+        map.set_mines_at((2, 2).into(), 0);
+        assert_eq!(map.bomb_count(), 2);
+
+        let mut map = Map::empty(4, 4).with_max_mines(2);
+        map.set_bombs(20, 7);
+        let mines: u16 = map.iter().map(|(_, tile)| tile.mines() as u16).sum();
+        assert_eq!(mines, 20);
+        assert!(map.iter().all(|(_, tile)| tile.mines() <= 2));
+    }
+
+    #[test]
+    fn void_tiles() {
+        let map = Map::with_bombs(3, 3, [(0, 0).into(), (2, 2).into()])
+            .with_mask(|c| c != Coordinates::new(2, 2) && c != Coordinates::new(1, 0));
+        assert_eq!(map.bomb_count(), 1);
+        assert_eq!(map.get((2, 2).into()), Some(&Tile::Void));
+        assert_eq!(map.playable_count(), 7);
+        assert_eq!(map.neighbors(Coordinates { x: 1, y: 1 }).count(), 6);
+        assert_eq!(map.neighbors(Coordinates { x: 2, y: 2 }).count(), 0);
+        assert_eq!(map.get((1, 1).into()), Some(&Tile::BombNeighbor(1)));
+        assert_eq!(map.without_bombs().playable_count(), 7);
+
+        // Mines only go to the playable tiles, as many as they can hold
+        let mut map = Map::empty(9, 9).with_shape(BoardShape::Circle);
+        map.set_bombs(200, 1);
+        assert_eq!(map.bomb_count() as usize, map.playable_count());
+        assert!(map
+            .iter()
+            .all(|(_, tile)| tile.is_bomb() || *tile == Tile::Void));
+    }
+
+    #[test]
+    fn three_bv() {
+        // One opening and no isolated number
+        let map = Map::with_bombs(4, 4, [(0, 0).into()]);
+        assert_eq!(map.three_bv(), 1);
+        // 2*2
+        // 2*311
+        // 112*1
+        let bombs = [(1, 2), (1, 1), (3, 0)].map(Coordinates::from);
+        let map = Map::with_bombs(5, 3, bombs);
+        // A single opening at the top right, bordered by 4 of the 10 numbers
+        assert_eq!(map.three_bv(), 1 + 6);
+        // No opening at all
+        let map = Map::with_bombs(2, 1, [(0, 0).into()]);
+        assert_eq!(map.three_bv(), 1);
+    }
+
+    #[test]
+    fn neighbors_near_u16_max() {
+        let map = Map::empty(u16::MAX, 1);
+        let last = u16::MAX - 1;
+        assert_eq!(sorted_neighbors(&map, last, 0), vec![(last - 1, 0)]);
+        assert_eq!(sorted_neighbors(&map, 0, 0), vec![(1, 0)]);
+    }
+}
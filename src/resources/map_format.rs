@@ -0,0 +1,251 @@
+//! Board layouts in the formats of other minesweeper tools
+//!
+//! - plain text grid, one line per row from the top, `*` for a mine, a digit
+//!   from `2` to `9` for a tile holding several mines, `.` for a safe tile
+//!   and `#` for a hole in the board shape
+//! - Minesweeper Arbiter board files (`.mbf`), the layout header also used by
+//!   RMV style replays: width and height bytes, the mine count as a big
+//!   endian `u16`, then an `x`, `y` byte pair per mine, `y` going down from
+//!   the top row
+use std::fmt;
+
+use super::map::Map;
+use super::tile::Tile;
+use crate::components::coordinates::Coordinates;
+
+/// Why a layout could not be read or written
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FormatError {
+    UnexpectedCharacter {
+        line: usize,
+        character: char,
+    },
+    /// Rows of a text grid with different lengths
+    RaggedRows {
+        line: usize,
+    },
+    EmptyBoard,
+    /// Board too large for the format
+    TooLarge,
+    Truncated,
+    MineOutOfBounds(Coordinates),
+    /// Tiles holding several mines, which the format can't describe
+    MultipleMines,
+    /// Holes in the board shape, which the format can't describe
+    VoidTiles,
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedCharacter { line, character } => {
+                write!(f, "unexpected character {:?} on line {}", character, line)
+            }
+            Self::RaggedRows { line } => write!(f, "line {} has a different length", line),
+            Self::EmptyBoard => write!(f, "empty board"),
+            Self::TooLarge => write!(f, "board too large for the format"),
+            Self::Truncated => write!(f, "truncated data"),
+            Self::MineOutOfBounds(c) => write!(f, "mine at ({}, {}) out of the board", c.x, c.y),
+            Self::MultipleMines => write!(f, "tiles with several mines are not supported"),
+            Self::VoidTiles => write!(f, "boards with holes are not supported"),
+        }
+    }
+}
+
+impl std::error::Error for FormatError {}
+
+impl Map {
+    /// Reads a plain text grid, blank lines being ignored
+    pub fn from_text_grid(text: &str) -> Result<Map, FormatError> {
+        let mut rows = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let row = line
+                .chars()
+                // Mines of the tile, `None` for a void one
+                .map(|c| match c {
+                    '*' => Ok(Some(1)),
+                    '.' => Ok(Some(0)),
+                    '2'..='9' => Ok(Some(c as u8 - b'0')),
+                    '#' => Ok(None),
+                    character => Err(FormatError::UnexpectedCharacter {
+                        line: i + 1,
+                        character,
+                    }),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            if rows
+                .first()
+                .is_some_and(|first: &Vec<Option<u8>>| first.len() != row.len())
+            {
+                return Err(FormatError::RaggedRows { line: i + 1 });
+            }
+            rows.push(row);
+        }
+        let height = u16::try_from(rows.len()).map_err(|_| FormatError::TooLarge)?;
+        let width = rows.first().map_or(Ok(0), |r| u16::try_from(r.len()));
+        let width = width.map_err(|_| FormatError::TooLarge)?;
+        if width == 0 || height == 0 {
+            return Err(FormatError::EmptyBoard);
+        }
+        // The first line is the top row
+        let mines: Vec<_> = rows
+            .iter()
+            .rev()
+            .enumerate()
+            .flat_map(|(y, row)| {
+                row.iter()
+                    .enumerate()
+                    .filter_map(move |(x, mines)| {
+                        Some((Coordinates::new(x as u16, y as u16), (*mines)?))
+                    })
+                    .filter(|(_, mines)| *mines > 0)
+            })
+            .collect();
+        let is_playable =
+            |c: Coordinates| rows[(height - 1 - c.y) as usize][c.x as usize].is_some();
+        let max_mines = mines.iter().map(|(_, mines)| *mines).max().unwrap_or(1);
+        let mut map = Map::with_bombs(width, height, mines.iter().map(|(coords, _)| *coords))
+            .with_max_mines(max_mines)
+            .with_mask(is_playable);
+        for (coords, mines) in mines {
+            if mines > 1 {
+                map.set_mines_at(coords, mines);
+            }
+        }
+        Ok(map)
+    }
+
+    /// Writes the layout as a plain text grid
+    pub fn to_text_grid(&self) -> String {
+        self.rows()
+            .rev()
+            .map(|row| {
+                row.iter()
+                    .map(|tile| match tile {
+                        Tile::Void => '#',
+                        Tile::Bomb(1) => '*',
+                        Tile::Bomb(mines) => (b'0' + mines) as char,
+                        _ => '.',
+                    })
+                    .chain(std::iter::once('\n'))
+                    .collect::<String>()
+            })
+            .collect()
+    }
+
+    /// Reads a Minesweeper Arbiter board file
+    pub fn from_mbf(bytes: &[u8]) -> Result<Map, FormatError> {
+        let [width, height, count_hi, count_lo, mines @ ..] = bytes else {
+            return Err(FormatError::Truncated);
+        };
+        let (width, height) = (*width as u16, *height as u16);
+        if width == 0 || height == 0 {
+            return Err(FormatError::EmptyBoard);
+        }
+        let count = u16::from_be_bytes([*count_hi, *count_lo]) as usize;
+        if mines.len() < count * 2 {
+            return Err(FormatError::Truncated);
+        }
+        let bombs = mines[..count * 2]
+            .chunks(2)
+            .map(|pair| {
+                let (x, y) = (pair[0] as u16, pair[1] as u16);
+                if x < width && y < height {
+                    Ok(Coordinates::new(x, height - 1 - y))
+                } else {
+                    Err(FormatError::MineOutOfBounds(Coordinates::new(x, y)))
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Map::with_bombs(width, height, bombs))
+    }
+
+    /// Writes the layout as a Minesweeper Arbiter board file, limited to
+    /// 255 tiles per side and a mine per tile
+    pub fn to_mbf(&self) -> Result<Vec<u8>, FormatError> {
+        if self.iter().any(|(_, tile)| tile.mines() > 1) {
+            return Err(FormatError::MultipleMines);
+        }
+        if self.iter().any(|(_, tile)| *tile == Tile::Void) {
+            return Err(FormatError::VoidTiles);
+        }
+        let width = u8::try_from(self.width()).map_err(|_| FormatError::TooLarge)?;
+        let height = u8::try_from(self.height()).map_err(|_| FormatError::TooLarge)?;
+        let mut bytes = vec![width, height];
+        bytes.extend(self.bomb_count().to_be_bytes());
+        for (coords, tile) in self.iter() {
+            if tile.is_bomb() {
+                bytes.push(coords.x as u8);
+                bytes.push((self.height() - 1 - coords.y) as u8);
+            }
+        }
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GRID: &str = "*...\n..*.\n....\n";
+
+    #[test]
+    fn text_grid_round_trip() {
+        let map = Map::from_text_grid(GRID).unwrap();
+        assert_eq!((map.width(), map.height(), map.bomb_count()), (4, 3, 2));
+        // First line is the top row
+        assert_eq!(map.get((0, 2).into()), Some(&Tile::Bomb(1)));
+        assert_eq!(map.get((2, 1).into()), Some(&Tile::Bomb(1)));
+        assert_eq!(map.to_text_grid(), GRID);
+        // Digits for tiles holding several mines
+        let grid = "3..\n.*.\n";
+        let map = Map::from_text_grid(grid).unwrap();
+        assert_eq!(map.get((0, 1).into()), Some(&Tile::Bomb(3)));
+        assert_eq!(map.bomb_count(), 4);
+        assert_eq!(map.to_text_grid(), grid);
+        assert_eq!(map.to_mbf().err(), Some(FormatError::MultipleMines));
+        // Holes in the shape
+        let grid = "#*.\n...\n";
+        let map = Map::from_text_grid(grid).unwrap();
+        assert_eq!(map.get((0, 1).into()), Some(&Tile::Void));
+        assert_eq!(map.playable_count(), 5);
+        assert_eq!(map.to_text_grid(), grid);
+        assert_eq!(map.to_mbf().err(), Some(FormatError::VoidTiles));
+    }
+
+    #[test]
+    fn text_grid_errors() {
+        assert_eq!(
+            Map::from_text_grid("*.\n.x\n").err(),
+            Some(FormatError::UnexpectedCharacter {
+                line: 2,
+                character: 'x'
+            })
+        );
+        assert_eq!(
+            Map::from_text_grid("*..\n.\n").err(),
+            Some(FormatError::RaggedRows { line: 2 })
+        );
+        assert_eq!(
+            Map::from_text_grid("\n").err(),
+            Some(FormatError::EmptyBoard)
+        );
+    }
+
+    #[test]
+    fn mbf_round_trip() {
+        let map = Map::from_text_grid(GRID).unwrap();
+        let bytes = map.to_mbf().unwrap();
+        assert_eq!(bytes, vec![4, 3, 0, 2, 2, 1, 0, 0]);
+        let decoded = Map::from_mbf(&bytes).unwrap();
+        assert!(decoded.iter().eq(map.iter()));
+        assert_eq!(
+            Map::from_mbf(&bytes[..6]).err(),
+            Some(FormatError::Truncated)
+        );
+    }
+}
@@ -1,5 +1,12 @@
 pub mod board_asset;
+pub mod board_code;
 pub mod board_options;
 pub mod board;
-pub(crate) mod map;
-pub(crate) mod tile;
\ No newline at end of file
+pub mod difficulty;
+pub mod history;
+pub mod map;
+pub mod map_format;
+pub mod shape;
+pub mod theme;
+pub mod tile;
+pub mod topology;
@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+
+use crate::components::coordinates::Coordinates;
+
+/// Outline of the playable area of a board, the tiles outside of it being
+/// void
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BoardShape {
+    /// Every tile of the grid
+    #[default]
+    Rectangle,
+    /// Ellipse touching the edges of the grid
+    Circle,
+    /// Square standing on a corner
+    Diamond,
+    Heart,
+}
+
+impl BoardShape {
+    pub const ALL: [BoardShape; 4] = [Self::Rectangle, Self::Circle, Self::Diamond, Self::Heart];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Rectangle => "Rectangle",
+            Self::Circle => "Circle",
+            Self::Diamond => "Diamond",
+            Self::Heart => "Heart",
+        }
+    }
+
+    /// Whether the tile of a `width` * `height` grid is playable
+    pub fn contains(&self, coordinates: Coordinates, (width, height): (u16, u16)) -> bool {
+        // Center of the tile, from -1 to 1 across the grid, `v` going up
+        let u = (coordinates.x as f32 + 0.5) / width as f32 * 2. - 1.;
+        let v = (coordinates.y as f32 + 0.5) / height as f32 * 2. - 1.;
+        match self {
+            Self::Rectangle => true,
+            Self::Circle => u * u + v * v <= 1.,
+            Self::Diamond => u.abs() + v.abs() <= 1.,
+            Self::Heart => {
+                // (x² + y² - 1)³ <= x²y³, spanning x in [-1.14, 1.14] and
+                // y in [-1, 1.25]
+                let x = u * 1.14;
+                let y = v * 1.125 + 0.125;
+                (x * x + y * y - 1.).powi(3) <= x * x * y.powi(3)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shapes_cut_the_grid() {
+        let size = (9, 9);
+        let count = |shape: BoardShape| {
+            (0..9)
+                .flat_map(|y| (0..9).map(move |x| Coordinates::new(x, y)))
+                .filter(|c| shape.contains(*c, size))
+                .count()
+        };
+        assert_eq!(count(BoardShape::Rectangle), 81);
+        for shape in [BoardShape::Circle, BoardShape::Diamond, BoardShape::Heart] {
+            assert!(shape.contains(Coordinates::new(4, 4), size), "{:?}", shape);
+            assert!(!shape.contains(Coordinates::new(0, 0), size), "{:?}", shape);
+            assert!(count(shape) > 81 / 3, "{:?}", shape);
+        }
+        // The heart lobes stand on both sides of a notch
+        let heart = BoardShape::Heart;
+        assert!(!heart.contains(Coordinates::new(4, 8), size));
+        assert!(heart.contains(Coordinates::new(2, 7), size));
+        assert!(heart.contains(Coordinates::new(6, 7), size));
+    }
+}
@@ -0,0 +1,72 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::board_asset::{BoardAsset, SpriteMaterial};
+
+/// Board color themes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Theme {
+    #[default]
+    Classic,
+    Dark,
+    Ocean,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 3] = [Self::Classic, Self::Dark, Self::Ocean];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Classic => "Classic",
+            Self::Dark => "Dark",
+            Self::Ocean => "Ocean",
+        }
+    }
+
+    /// Builds the board assets of the theme
+    pub fn board_asset(&self, asset_server: &AssetServer) -> BoardAsset {
+        let (board, tile, covered) = match self {
+            Self::Classic => (Color::WHITE, Color::DARK_GRAY, Color::GRAY),
+            Self::Dark => (
+                Color::rgb(0.1, 0.1, 0.1),
+                Color::rgb(0.2, 0.2, 0.22),
+                Color::rgb(0.35, 0.35, 0.4),
+            ),
+            Self::Ocean => (
+                Color::rgb(0.85, 0.93, 1.0),
+                Color::rgb(0.16, 0.3, 0.45),
+                Color::rgb(0.3, 0.55, 0.75),
+            ),
+        };
+        BoardAsset {
+            label: self.label().to_string(),
+            board_material: SpriteMaterial {
+                color: board,
+                ..Default::default()
+            },
+            tile_material: SpriteMaterial {
+                color: tile,
+                ..Default::default()
+            },
+            covered_tile_material: SpriteMaterial {
+                color: covered,
+                ..Default::default()
+            },
+            bomb_counter_font: asset_server.load("fonts/pixeled.ttf"),
+            bomb_counter_colors: BoardAsset::default_color(),
+            flag_material: SpriteMaterial {
+                color: Color::WHITE,
+                texture: asset_server.load("sprites/flag.png"),
+            },
+            question_material: SpriteMaterial {
+                color: Color::WHITE,
+                texture: asset_server.load("sprites/question.png"),
+            },
+            bomb_material: SpriteMaterial {
+                color: Color::WHITE,
+                texture: asset_server.load("sprites/bomb.png"),
+            },
+            hex_texture: asset_server.load("sprites/hex.png"),
+        }
+    }
+}
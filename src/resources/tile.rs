@@ -1,19 +1,31 @@
 #[cfg(feature = "debug")]
 use colored::Colorize;
 
-#[derive(Debug, PartialEq, Eq)]
+/// Hidden content of a board tile
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Tile {
-    // Is a Bomb
-    Bomb,
+    // Is a Bomb, holding at least one mine
+    Bomb(u8),
     // Is a Bomb neighbor
     BombNeighbor(u8),
     // Empty tile
     Empty,
+    // Hole in the board shape, neither a bomb nor revealable
+    Void,
 }
 
 impl Tile {
+    /// Is the tile a bomb
     pub const fn is_bomb(&self) -> bool {
-        matches!(self, Self::Bomb)
+        matches!(self, Self::Bomb(_))
+    }
+
+    /// Mines held by the tile, `0` for a safe tile
+    pub const fn mines(&self) -> u8 {
+        match self {
+            Self::Bomb(mines) => *mines,
+            _ => 0,
+        }
     }
 
     #[cfg(feature = "debug")]
@@ -21,7 +33,8 @@ impl Tile {
         format!(
             "{}",
             match self {
-                Tile::Bomb => "*".bright_red(),
+                Tile::Bomb(1) => "*".bright_red(),
+                Tile::Bomb(v) => v.to_string().bright_red(),
                 Tile::BombNeighbor(v) => match v {
                     1 => "1".cyan(),
                     2 => "2".green(),
@@ -29,6 +42,7 @@ impl Tile {
                     _ => v.to_string().red(),
                 },
                 Tile::Empty => " ".normal(),
+                Tile::Void => "#".bright_black(),
             }
         )
     }
@@ -0,0 +1,251 @@
+use bevy::prelude::Vec2;
+use serde::{Deserialize, Serialize};
+
+use crate::components::coordinates::Coordinates;
+
+// Delta coordinates for all 8 square neighbors
+const SQUARE_COORDINATES: [(i8, i8); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+// Delta coordinates of the 4 orthogonal neighbors
+const CROSS_COORDINATES: [(i8, i8); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+
+// Delta coordinates of the 8 tiles a knight's move away
+const KNIGHT_COORDINATES: [(i8, i8); 8] = [
+    (-1, -2),
+    (1, -2),
+    (-2, -1),
+    (2, -1),
+    (-2, 1),
+    (2, 1),
+    (-1, 2),
+    (1, 2),
+];
+
+// Delta coordinates of the 24 tiles at most 2 tiles away
+const RADIUS_2_COORDINATES: [(i8, i8); 24] = [
+    (-2, -2),
+    (-1, -2),
+    (0, -2),
+    (1, -2),
+    (2, -2),
+    (-2, -1),
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (2, -1),
+    (-2, 0),
+    (-1, 0),
+    (1, 0),
+    (2, 0),
+    (-2, 1),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+    (2, 1),
+    (-2, 2),
+    (-1, 2),
+    (0, 2),
+    (1, 2),
+    (2, 2),
+];
+
+// Delta coordinates of the 6 hex neighbors, odd rows being shifted right
+const HEX_EVEN_ROW_COORDINATES: [(i8, i8); 6] =
+    [(-1, -1), (0, -1), (-1, 0), (1, 0), (-1, 1), (0, 1)];
+const HEX_ODD_ROW_COORDINATES: [(i8, i8); 6] = [(0, -1), (1, -1), (-1, 0), (1, 0), (0, 1), (1, 1)];
+
+// Height of a pointy top hexagon whose width is `1`
+const HEX_HEIGHT: f32 = 1.154_700_5;
+// Vertical distance between two hex rows, three quarters of the height
+const HEX_ROW_STEP: f32 = 0.866_025_4;
+
+/// Shape of the tiles and which tiles are neighbors
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Topology {
+    /// Square tiles with 8 neighbors
+    #[default]
+    Square,
+    /// Pointy top hexagons with 6 neighbors, rows being stacked with every
+    /// odd row shifted right by half a tile
+    Hex,
+}
+
+impl Topology {
+    pub const ALL: [Topology; 2] = [Self::Square, Self::Hex];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Square => "Square",
+            Self::Hex => "Hex",
+        }
+    }
+
+    /// Delta coordinates of the neighbors of a tile, hex tiles always
+    /// having their 6 adjacent tiles as neighbors
+    pub fn neighbor_deltas(
+        &self,
+        coordinates: Coordinates,
+        neighborhood: Neighborhood,
+    ) -> &'static [(i8, i8)] {
+        match self {
+            Self::Square => neighborhood.deltas(),
+            Self::Hex if coordinates.y % 2 == 1 => &HEX_ODD_ROW_COORDINATES,
+            Self::Hex => &HEX_EVEN_ROW_COORDINATES,
+        }
+    }
+
+    /// World size of a `width` * `height` board
+    pub fn board_size(&self, (width, height): (u16, u16), tile_size: f32) -> Vec2 {
+        let (width, height) = (width as f32, height as f32);
+        match self {
+            Self::Square => Vec2::new(width, height) * tile_size,
+            Self::Hex => {
+                // Shifted odd rows stick out by half a tile
+                let shift = if height > 1. { 0.5 } else { 0. };
+                Vec2::new(width + shift, (height - 1.) * HEX_ROW_STEP + HEX_HEIGHT) * tile_size
+            }
+        }
+    }
+
+    /// Offset between a tile and its copy across the edges of a wrapping
+    /// `width` * `height` board
+    pub fn wrap_period(&self, (width, height): (u16, u16), tile_size: f32) -> Vec2 {
+        let (width, height) = (width as f32, height as f32);
+        match self {
+            Self::Square => Vec2::new(width, height) * tile_size,
+            Self::Hex => Vec2::new(width, height * HEX_ROW_STEP) * tile_size,
+        }
+    }
+
+    /// Sprite size of a tile, its width being `size`
+    pub fn tile_sprite_size(&self, size: f32) -> Vec2 {
+        match self {
+            Self::Square => Vec2::splat(size),
+            Self::Hex => Vec2::new(size, size * HEX_HEIGHT),
+        }
+    }
+
+    /// Center of a tile relative to the bottom left corner of the board
+    pub fn tile_center(&self, coordinates: Coordinates, tile_size: f32) -> Vec2 {
+        let (x, y) = (coordinates.x as f32, coordinates.y as f32);
+        match self {
+            Self::Square => Vec2::new(x + 0.5, y + 0.5) * tile_size,
+            Self::Hex => {
+                let shift = if coordinates.y % 2 == 1 { 0.5 } else { 0. };
+                Vec2::new(x + 0.5 + shift, y * HEX_ROW_STEP + HEX_HEIGHT / 2.) * tile_size
+            }
+        }
+    }
+
+    /// Tile under a position relative to the bottom left corner of the
+    /// board, which may lie out of the map
+    pub fn tile_at(&self, position: Vec2, tile_size: f32) -> Option<Coordinates> {
+        let position = position / tile_size;
+        if position.x < 0. || position.y < 0. {
+            return None;
+        }
+        match self {
+            Self::Square => Some(Coordinates::new(position.x as u16, position.y as u16)),
+            Self::Hex => {
+                // The closest center among the tiles around the estimate
+                let row = ((position.y - HEX_HEIGHT / 2.) / HEX_ROW_STEP).round() as i32;
+                let column = position.x.round() as i32;
+                (row - 1..=row + 1)
+                    .flat_map(|y| (column - 1..=column + 1).map(move |x| (x, y)))
+                    .filter_map(|(x, y)| {
+                        Some(Coordinates::new(
+                            u16::try_from(x).ok()?,
+                            u16::try_from(y).ok()?,
+                        ))
+                    })
+                    .min_by(|a, b| {
+                        let distance = |c| self.tile_center(c, 1.).distance_squared(position);
+                        distance(*a).total_cmp(&distance(*b))
+                    })
+            }
+        }
+    }
+}
+
+/// Which square tiles are neighbors, whose bombs are counted by the numbers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Neighborhood {
+    /// The 8 surrounding tiles of the classic rules
+    #[default]
+    Moore,
+    /// The 4 orthogonal tiles
+    Cross,
+    /// The 8 tiles a knight's move away
+    Knight,
+    /// The 24 tiles at most 2 tiles away
+    Radius2,
+}
+
+impl Neighborhood {
+    pub const ALL: [Neighborhood; 4] = [Self::Moore, Self::Cross, Self::Knight, Self::Radius2];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Moore => "Normal",
+            Self::Cross => "Cross",
+            Self::Knight => "Knight",
+            Self::Radius2 => "Radius 2",
+        }
+    }
+
+    /// Delta coordinates of the neighbors of any tile
+    pub fn deltas(&self) -> &'static [(i8, i8)] {
+        match self {
+            Self::Moore => &SQUARE_COORDINATES,
+            Self::Cross => &CROSS_COORDINATES,
+            Self::Knight => &KNIGHT_COORDINATES,
+            Self::Radius2 => &RADIUS_2_COORDINATES,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tiles_are_found_from_their_center() {
+        for topology in Topology::ALL {
+            for coords in [(0, 0), (3, 0), (0, 1), (4, 3), (2, 6)].map(Coordinates::from) {
+                let center = topology.tile_center(coords, 30.);
+                assert_eq!(
+                    topology.tile_at(center, 30.),
+                    Some(coords),
+                    "{:?}",
+                    topology
+                );
+                // Close to the edge of the tile
+                let near_edge = center + Vec2::new(13., 0.);
+                assert_eq!(topology.tile_at(near_edge, 30.), Some(coords));
+            }
+        }
+    }
+
+    #[test]
+    fn hex_rows_are_shifted() {
+        let hex = Topology::Hex;
+        assert_eq!(hex.tile_center((0, 0).into(), 1.).x, 0.5);
+        assert_eq!(hex.tile_center((0, 1).into(), 1.).x, 1.);
+        // Every tile of a 3*3 board fits in the board size
+        let size = hex.board_size((3, 3), 1.);
+        let half = hex.tile_sprite_size(1.) / 2.;
+        let top_right = hex.tile_center((2, 1).into(), 1.) + half;
+        assert!(top_right.x <= size.x && top_right.y <= size.y);
+        let top = hex.tile_center((0, 2).into(), 1.) + half;
+        assert!((top.y - size.y).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,209 @@
+use std::collections::BTreeSet;
+
+use super::visible::{VisibleBoard, VisibleTile};
+
+/// Revealed number constraining its unknown covered neighbors. Flags are not
+/// trusted so a misplaced flag can't lead to a wrong deduction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Constraint {
+    /// Index of the revealed tile
+    pub source: usize,
+    /// Unknown covered neighbors, flagged or not, sorted
+    pub cells: Vec<usize>,
+    /// Bombs among `cells`
+    pub mines: usize,
+}
+
+/// Why a tile is known to be safe or a mine
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reason {
+    /// The number at `source` alone settles its covered neighbors
+    Single { source: usize },
+    /// The covered neighbors of `inner` are all neighbors of `outer`, which
+    /// settles the remaining neighbors of `outer`
+    Subset { inner: usize, outer: usize },
+}
+
+/// A tile proven safe or a mine
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Deduction {
+    pub cell: usize,
+    pub mine: bool,
+    pub reason: Reason,
+}
+
+/// Constraints of every revealed number bordering tiles which are still
+/// unknown, `known` holding the tiles already proven safe (`false`) or mines
+pub fn constraints(board: &VisibleBoard, known: &[Option<bool>]) -> Vec<Constraint> {
+    (0..board.len())
+        .filter_map(|source| {
+            let number = match board.tile(source) {
+                VisibleTile::Revealed(v) => v as usize,
+                _ => return None,
+            };
+            let neighbors = board.neighbors(source);
+            let mut cells: Vec<usize> = neighbors
+                .iter()
+                .copied()
+                .filter(|n| board.tile(*n).is_covered() && known[*n].is_none())
+                .collect();
+            if cells.is_empty() {
+                return None;
+            }
+            let found = neighbors
+                .iter()
+                .filter(|n| board.tile(**n) == VisibleTile::Mine || known[**n] == Some(true))
+                .count();
+            cells.sort_unstable();
+            Some(Constraint {
+                source,
+                cells,
+                mines: number.checked_sub(found)?,
+            })
+        })
+        .collect()
+}
+
+/// Proves as many covered tiles as possible, from single numbers first and
+/// then from pairs of numbers, feeding every proven tile back until nothing
+/// new is found. Each tile is reported once, in the order it was proven
+pub fn deduce(board: &VisibleBoard) -> Vec<Deduction> {
+    let mut known = vec![None; board.len()];
+    let mut res = Vec::new();
+    loop {
+        let constraints = constraints(board, &known);
+        let mut found = single_deductions(&constraints);
+        if found.is_empty() {
+            found = subset_deductions(&constraints);
+        }
+        if found.is_empty() {
+            return res;
+        }
+        for deduction in &found {
+            known[deduction.cell] = Some(deduction.mine);
+        }
+        res.extend(found);
+    }
+}
+
+fn single_deductions(constraints: &[Constraint]) -> Vec<Deduction> {
+    let mut seen = BTreeSet::new();
+    let mut res = Vec::new();
+    for c in constraints {
+        let mine = match c.mines {
+            0 => false,
+            m if m == c.cells.len() => true,
+            _ => continue,
+        };
+        let reason = Reason::Single { source: c.source };
+        for cell in &c.cells {
+            if seen.insert(*cell) {
+                res.push(Deduction {
+                    cell: *cell,
+                    mine,
+                    reason,
+                });
+            }
+        }
+    }
+    res
+}
+
+fn subset_deductions(constraints: &[Constraint]) -> Vec<Deduction> {
+    let mut seen = BTreeSet::new();
+    let mut res = Vec::new();
+    for inner in constraints {
+        for outer in constraints {
+            if inner.source == outer.source
+                || inner.cells.len() >= outer.cells.len()
+                || !is_subset(&inner.cells, &outer.cells)
+            {
+                continue;
+            }
+            let rest: Vec<usize> = outer
+                .cells
+                .iter()
+                .copied()
+                .filter(|c| inner.cells.binary_search(c).is_err())
+                .collect();
+            let mines = match outer.mines.checked_sub(inner.mines) {
+                Some(m) => m,
+                // Contradicting numbers, nothing can be proven from them
+                None => continue,
+            };
+            let mine = match mines {
+                0 => false,
+                m if m == rest.len() => true,
+                _ => continue,
+            };
+            let reason = Reason::Subset {
+                inner: inner.source,
+                outer: outer.source,
+            };
+            for cell in rest {
+                if seen.insert(cell) {
+                    res.push(Deduction { cell, mine, reason });
+                }
+            }
+        }
+    }
+    res
+}
+
+// Both slices are sorted
+fn is_subset(small: &[usize], big: &[usize]) -> bool {
+    small.iter().all(|c| big.binary_search(c).is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::coordinates::Coordinates;
+    use crate::resources::map::Map;
+
+    // Bottom row covered, the rest revealed
+    fn bottom_covered(map: &Map) -> VisibleBoard {
+        VisibleBoard::from_map(map, |c| c.y == 0, |_| false)
+    }
+
+    fn proven(board: &VisibleBoard) -> Vec<(u16, bool)> {
+        let mut res: Vec<_> = deduce(board)
+            .into_iter()
+            .map(|d| (board.coords(d.cell).x, d.mine))
+            .collect();
+        res.sort();
+        res
+    }
+
+    #[test]
+    fn chained_single_deductions() {
+        // 11100
+        // .*...
+        let map = Map::with_bombs(5, 2, [Coordinates::new(1, 0)]);
+        let board = bottom_covered(&map);
+        assert_eq!(
+            proven(&board),
+            vec![(0, false), (1, true), (2, false), (3, false), (4, false)]
+        );
+    }
+
+    #[test]
+    fn subset_deduction() {
+        // 111
+        // .*.
+        let map = Map::with_bombs(3, 2, [Coordinates::new(1, 0)]);
+        let board = bottom_covered(&map);
+        let deductions = deduce(&board);
+        assert!(matches!(deductions[0].reason, Reason::Subset { .. }));
+        assert_eq!(proven(&board), vec![(0, false), (1, true), (2, false)]);
+    }
+
+    #[test]
+    fn no_deduction_on_a_coin_flip() {
+        // 11
+        // *.
+        let map = Map::with_bombs(2, 2, [Coordinates::new(0, 0)]);
+        let board = bottom_covered(&map);
+        assert!(deduce(&board).is_empty());
+    }
+}
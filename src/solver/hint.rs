@@ -0,0 +1,105 @@
+use crate::components::coordinates::Coordinates;
+
+use super::deduction::{deduce, Deduction, Reason};
+use super::probability::Probabilities;
+use super::visible::{VisibleBoard, VisibleTile};
+
+/// What the hint tells about its tile
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HintKind {
+    /// The tile is provably safe
+    Safe,
+    /// The tile is provably a bomb
+    Mine,
+    /// Nothing can be proven, the tile has the lowest bomb probability
+    Guess { risk: f32 },
+}
+
+/// A single tile worth playing, with the reasoning behind it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hint {
+    pub coords: Coordinates,
+    pub kind: HintKind,
+    pub explanation: String,
+}
+
+impl Hint {
+    /// Picks a proven safe tile, else a proven bomb which isn't flagged yet,
+    /// else the least risky covered tile
+    pub fn find(board: &VisibleBoard) -> Option<Self> {
+        let deductions = deduce(board);
+        let safe = deductions
+            .iter()
+            .find(|d| !d.mine && board.tile(d.cell) == VisibleTile::Covered);
+        let mine = || {
+            deductions
+                .iter()
+                .find(|d| d.mine && board.tile(d.cell) == VisibleTile::Covered)
+        };
+        if let Some(deduction) = safe.or_else(mine) {
+            return Some(Self::from_deduction(board, deduction));
+        }
+        let (cell, risk) = Probabilities::compute(board).safest(board)?;
+        let coords = board.coords(cell);
+        Some(Self {
+            coords,
+            kind: HintKind::Guess { risk },
+            explanation: format!(
+                "Nothing can be proven, {} is the least risky tile ({:.0}% bomb)",
+                fmt_coords(coords),
+                risk * 100.
+            ),
+        })
+    }
+
+    fn from_deduction(board: &VisibleBoard, deduction: &Deduction) -> Self {
+        let coords = board.coords(deduction.cell);
+        let (kind, verdict) = if deduction.mine {
+            (HintKind::Mine, "a bomb")
+        } else {
+            (HintKind::Safe, "safe")
+        };
+        let number = |i: usize| match board.tile(i) {
+            VisibleTile::Revealed(v) => format!("the {} at {}", v, fmt_coords(board.coords(i))),
+            _ => fmt_coords(board.coords(i)),
+        };
+        let explanation = match deduction.reason {
+            Reason::Single { source } if deduction.mine => format!(
+                "{} has as many covered neighbors left as missing bombs, so {} is {}",
+                capitalize(&number(source)),
+                fmt_coords(coords),
+                verdict
+            ),
+            Reason::Single { source } => format!(
+                "{} already touches all of its bombs, so {} is {}",
+                capitalize(&number(source)),
+                fmt_coords(coords),
+                verdict
+            ),
+            Reason::Subset { inner, outer } => format!(
+                "The covered neighbors of {} are all next to {} too, so {} is {}",
+                number(inner),
+                number(outer),
+                fmt_coords(coords),
+                verdict
+            ),
+        };
+        Self {
+            coords,
+            kind,
+            explanation,
+        }
+    }
+}
+
+fn fmt_coords(coords: Coordinates) -> String {
+    format!("({}, {})", coords.x, coords.y)
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(c) => c.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
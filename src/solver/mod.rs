@@ -0,0 +1,19 @@
+//! Minesweeper solver working on what the player can see, so it never leaks
+//! the hidden `Map`
+pub mod deduction;
+pub mod hint;
+pub mod probability;
+pub mod visible;
+
+pub use deduction::{Constraint, Deduction, Reason};
+pub use hint::{Hint, HintKind};
+pub use probability::Probabilities;
+pub use visible::{VisibleBoard, VisibleTile};
+
+use crate::resources::map::Map;
+
+/// Whether the solver understands the board: it assumes every bomb tile
+/// holds a single mine
+pub fn supports(map: &Map) -> bool {
+    map.max_mines() == 1
+}
@@ -0,0 +1,372 @@
+use std::collections::BTreeSet;
+
+use super::deduction::{constraints, deduce, Constraint};
+use super::visible::{VisibleBoard, VisibleTile};
+
+/// Number of search steps allowed per group of tiles before falling back to
+/// an estimate
+const SEARCH_BUDGET: usize = 200_000;
+
+/// Bomb probability of every tile of a visible board
+#[derive(Debug, Clone, PartialEq)]
+pub struct Probabilities {
+    /// `None` for uncovered tiles
+    pub values: Vec<Option<f32>>,
+    /// Whether every value is exact, large undecided areas being estimated
+    pub exact: bool,
+}
+
+impl Probabilities {
+    /// Computes the probability of each covered tile being a bomb, given the
+    /// revealed numbers and the total bomb count, every consistent layout
+    /// being equally likely. Flags are not trusted
+    pub fn compute(board: &VisibleBoard) -> Self {
+        let mut values: Vec<Option<f32>> = board
+            .tiles()
+            .iter()
+            .map(|t| t.is_covered().then_some(0.))
+            .collect();
+        // Tiles proven by plain deduction don't need to be enumerated
+        let mut known = vec![None; board.len()];
+        for deduction in deduce(board) {
+            known[deduction.cell] = Some(deduction.mine);
+            values[deduction.cell] = Some(if deduction.mine { 1. } else { 0. });
+        }
+        let constraints = constraints(board, &known);
+        let found = board.known_mines() + known.iter().filter(|k| **k == Some(true)).count();
+        let remaining = (board.bomb_count() as usize).saturating_sub(found);
+
+        let mut frontier = vec![false; board.len()];
+        for c in &constraints {
+            for cell in &c.cells {
+                frontier[*cell] = true;
+            }
+        }
+        let interior: Vec<usize> = board
+            .covered()
+            .filter(|i| known[*i].is_none() && !frontier[*i])
+            .collect();
+
+        let mut exact = true;
+        let mut groups = Vec::new();
+        for cells in components(&constraints, board.len()) {
+            match Group::enumerate(&cells, &constraints, remaining) {
+                Some(group) => groups.push(group),
+                None => {
+                    exact = false;
+                    estimate(&cells, &constraints, &mut values);
+                }
+            }
+        }
+
+        // Weight of each total of frontier bombs, the interior tiles sharing
+        // the remaining bombs evenly
+        let totals = groups
+            .iter()
+            .fold(vec![1f64], |acc, g| convolve(&acc, &g.counts));
+        let weight = |frontier_mines: usize| {
+            if frontier_mines > remaining || remaining - frontier_mines > interior.len() {
+                return f64::NEG_INFINITY;
+            }
+            ln_binomial(interior.len(), remaining - frontier_mines)
+        };
+        let ln_max = (0..totals.len())
+            .filter(|k| totals[*k] > 0.)
+            .map(weight)
+            .fold(f64::NEG_INFINITY, f64::max);
+        if ln_max == f64::NEG_INFINITY {
+            // Contradicting numbers, every unknown tile is equally likely
+            let unknown = board.covered().filter(|i| known[*i].is_none()).count();
+            let density = remaining as f32 / unknown.max(1) as f32;
+            for i in board.covered().filter(|i| known[*i].is_none()) {
+                values[i] = Some(density);
+            }
+            return Self {
+                values,
+                exact: false,
+            };
+        }
+        let scale = |k: usize| (weight(k) - ln_max).exp();
+        let total: f64 = (0..totals.len()).map(|k| totals[k] * scale(k)).sum();
+
+        for (g, group) in groups.iter().enumerate() {
+            // Totals of every group but this one
+            let others = groups
+                .iter()
+                .enumerate()
+                .filter(|(o, _)| *o != g)
+                .fold(vec![1f64], |acc, (_, o)| convolve(&acc, &o.counts));
+            for (c, cell) in group.cells.iter().enumerate() {
+                let mut mines = 0.;
+                for (k, per_cell) in group.mines.iter().enumerate() {
+                    if per_cell[c] == 0. {
+                        continue;
+                    }
+                    for (o, count) in others.iter().enumerate() {
+                        mines += per_cell[c] * count * scale(k + o);
+                    }
+                }
+                values[*cell] = Some((mines / total) as f32);
+            }
+        }
+
+        // Expected bombs left for the interior tiles
+        if !interior.is_empty() {
+            let expected: f64 = (0..totals.len())
+                .map(|k| totals[k] * scale(k) * remaining.saturating_sub(k) as f64)
+                .sum::<f64>()
+                / total;
+            let p = (expected / interior.len() as f64) as f32;
+            for i in interior {
+                values[i] = Some(p);
+            }
+        }
+
+        // Uncovered bombs are certain
+        for (i, tile) in board.tiles().iter().enumerate() {
+            if *tile == VisibleTile::Mine {
+                values[i] = None;
+            }
+        }
+        Self { values, exact }
+    }
+
+    /// Covered tile, not flagged, with the lowest bomb probability
+    pub fn safest(&self, board: &VisibleBoard) -> Option<(usize, f32)> {
+        self.values
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| board.tile(*i) == VisibleTile::Covered)
+            .filter_map(|(i, p)| p.map(|p| (i, p)))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+    }
+}
+
+/// Layouts of a group of frontier tiles linked by shared numbers
+struct Group {
+    cells: Vec<usize>,
+    /// Number of layouts for each bomb count
+    counts: Vec<f64>,
+    /// For each bomb count, number of layouts where each cell is a bomb
+    mines: Vec<Vec<f64>>,
+}
+
+impl Group {
+    /// Enumerates every layout satisfying the constraints, `None` when the
+    /// search runs out of budget
+    fn enumerate(cells: &[usize], constraints: &[Constraint], max_mines: usize) -> Option<Self> {
+        let position = |cell: usize| cells.binary_search(&cell).ok();
+        // Constraints touching the group, with the group positions of their cells
+        let local: Vec<(Vec<usize>, usize)> = constraints
+            .iter()
+            .filter(|c| c.cells.iter().any(|cell| position(*cell).is_some()))
+            .map(|c| {
+                (
+                    c.cells.iter().filter_map(|c| position(*c)).collect(),
+                    c.mines,
+                )
+            })
+            .collect();
+        let mut cell_constraints = vec![Vec::new(); cells.len()];
+        for (i, (positions, _)) in local.iter().enumerate() {
+            for p in positions {
+                cell_constraints[*p].push(i);
+            }
+        }
+        let mut search = Search {
+            local: &local,
+            cell_constraints: &cell_constraints,
+            assigned: vec![0; local.len()],
+            unassigned: local.iter().map(|(p, _)| p.len()).collect(),
+            layout: vec![false; cells.len()],
+            counts: vec![0.; cells.len() + 1],
+            mines: vec![vec![0.; cells.len()]; cells.len() + 1],
+            max_mines,
+            steps: 0,
+        };
+        if !search.run(0, 0) {
+            return None;
+        }
+        Some(Self {
+            cells: cells.to_vec(),
+            counts: search.counts,
+            mines: search.mines,
+        })
+    }
+}
+
+struct Search<'a> {
+    local: &'a [(Vec<usize>, usize)],
+    cell_constraints: &'a [Vec<usize>],
+    /// Bombs placed and cells left per constraint
+    assigned: Vec<usize>,
+    unassigned: Vec<usize>,
+    layout: Vec<bool>,
+    counts: Vec<f64>,
+    mines: Vec<Vec<f64>>,
+    max_mines: usize,
+    steps: usize,
+}
+
+impl Search<'_> {
+    /// Returns `false` once the budget is exhausted
+    fn run(&mut self, position: usize, placed: usize) -> bool {
+        self.steps += 1;
+        if self.steps > SEARCH_BUDGET {
+            return false;
+        }
+        if position == self.layout.len() {
+            self.counts[placed] += 1.;
+            for (p, mine) in self.layout.iter().enumerate() {
+                if *mine {
+                    self.mines[placed][p] += 1.;
+                }
+            }
+            return true;
+        }
+        for mine in [false, true] {
+            if mine && placed == self.max_mines {
+                continue;
+            }
+            if self.assign(position, mine) {
+                self.layout[position] = mine;
+                let ok = self.run(position + 1, placed + mine as usize);
+                self.layout[position] = false;
+                self.unassign(position, mine);
+                if !ok {
+                    return false;
+                }
+            } else {
+                self.unassign(position, mine);
+            }
+        }
+        true
+    }
+
+    /// Assigns the cell, returning whether its constraints can still be met
+    fn assign(&mut self, position: usize, mine: bool) -> bool {
+        let mut ok = true;
+        for c in &self.cell_constraints[position] {
+            self.unassigned[*c] -= 1;
+            self.assigned[*c] += mine as usize;
+            let target = self.local[*c].1;
+            ok &= self.assigned[*c] <= target && self.assigned[*c] + self.unassigned[*c] >= target;
+        }
+        ok
+    }
+
+    fn unassign(&mut self, position: usize, mine: bool) {
+        for c in &self.cell_constraints[position] {
+            self.unassigned[*c] += 1;
+            self.assigned[*c] -= mine as usize;
+        }
+    }
+}
+
+/// Splits the constrained cells into groups linked by shared constraints,
+/// each group sorted
+fn components(constraints: &[Constraint], len: usize) -> Vec<Vec<usize>> {
+    // Union find over the tile indices
+    let mut parent: Vec<usize> = (0..len).collect();
+    fn find(parent: &mut [usize], i: usize) -> usize {
+        let mut root = i;
+        while parent[root] != root {
+            root = parent[root];
+        }
+        let mut i = i;
+        while parent[i] != root {
+            let next = parent[i];
+            parent[i] = root;
+            i = next;
+        }
+        root
+    }
+    let mut cells = BTreeSet::new();
+    for c in constraints {
+        cells.extend(c.cells.iter().copied());
+        for pair in c.cells.windows(2) {
+            let (a, b) = (find(&mut parent, pair[0]), find(&mut parent, pair[1]));
+            parent[a] = b;
+        }
+    }
+    let mut groups: Vec<(usize, Vec<usize>)> = Vec::new();
+    for cell in cells {
+        let root = find(&mut parent, cell);
+        match groups.iter_mut().find(|(r, _)| *r == root) {
+            Some((_, group)) => group.push(cell),
+            None => groups.push((root, vec![cell])),
+        }
+    }
+    groups.into_iter().map(|(_, group)| group).collect()
+}
+
+/// Worst ratio of the numbers around each cell, for groups too large to enumerate
+fn estimate(cells: &[usize], constraints: &[Constraint], values: &mut [Option<f32>]) {
+    for cell in cells {
+        values[*cell] = constraints
+            .iter()
+            .filter(|c| c.cells.binary_search(cell).is_ok())
+            .map(|c| c.mines as f32 / c.cells.len() as f32)
+            .reduce(f32::max);
+    }
+}
+
+fn convolve(a: &[f64], b: &[f64]) -> Vec<f64> {
+    let mut res = vec![0.; a.len() + b.len() - 1];
+    for (i, x) in a.iter().enumerate() {
+        for (j, y) in b.iter().enumerate() {
+            res[i + j] += x * y;
+        }
+    }
+    res
+}
+
+fn ln_binomial(n: usize, k: usize) -> f64 {
+    let k = k.min(n - k);
+    (0..k).map(|i| ((n - i) as f64 / (i + 1) as f64).ln()).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::coordinates::Coordinates;
+    use crate::resources::map::Map;
+
+    fn assert_close(a: Option<f32>, b: f32) {
+        let a = a.unwrap();
+        assert!((a - b).abs() < 1e-4, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn coin_flip() {
+        // 11
+        // *.
+        let map = Map::with_bombs(2, 2, [Coordinates::new(0, 0)]);
+        let board = VisibleBoard::from_map(&map, |c| c.y == 0, |_| false);
+        let probabilities = Probabilities::compute(&board);
+        assert!(probabilities.exact);
+        assert_close(probabilities.values[0], 0.5);
+        assert_close(probabilities.values[1], 0.5);
+        assert_eq!(probabilities.values[2], None);
+    }
+
+    #[test]
+    fn interior_shares_remaining_bombs() {
+        // Only (0, 0) is revealed, a 1 with three covered neighbors, and one
+        // of the 5 other tiles holds the second bomb
+        //  ...
+        //  ...
+        //  1*.
+        let map = Map::with_bombs(3, 3, [Coordinates::new(1, 0), Coordinates::new(2, 2)]);
+        let board = VisibleBoard::from_map(&map, |c| c != Coordinates::new(0, 0), |_| false);
+        let probabilities = Probabilities::compute(&board);
+        assert!(probabilities.exact);
+        // Each neighbor of the 1 is a bomb in a third of the layouts
+        for coords in [(1, 0), (0, 1), (1, 1)] {
+            assert_close(probabilities.values[board.index(coords.into())], 1. / 3.);
+        }
+        for coords in [(2, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            assert_close(probabilities.values[board.index(coords.into())], 1. / 5.);
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use crate::components::coordinates::Coordinates;
+use crate::resources::board::Board;
+use crate::resources::map::Map;
+use crate::resources::tile::Tile;
+
+/// What the player knows about a tile
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VisibleTile {
+    Covered,
+    /// Covered tile flagged by the player, which may be wrong
+    Flagged,
+    /// Uncovered tile showing its bomb neighbor count
+    Revealed(u8),
+    /// Uncovered bomb
+    Mine,
+    /// Hole in the board shape, without neighbors
+    Void,
+}
+
+impl VisibleTile {
+    pub fn is_covered(&self) -> bool {
+        matches!(self, Self::Covered | Self::Flagged)
+    }
+}
+
+/// Visible state of a board: tile numbers of uncovered tiles, flags and the
+/// total bomb count, but nothing of the covered tiles
+#[derive(Debug, Clone)]
+pub struct VisibleBoard {
+    width: u16,
+    height: u16,
+    bomb_count: u16,
+    tiles: Vec<VisibleTile>,
+    neighbors: Vec<Vec<usize>>,
+}
+
+impl VisibleBoard {
+    /// Builds the visible state of a board resource
+    pub fn from_board(board: &Board) -> Self {
+        Self::from_map(
+            &board.tile_map,
+            |c| board.is_covered(&c),
+            |c| board.is_flagged(&c),
+        )
+    }
+
+    /// Builds the visible state of a map, only reading the tiles for which
+    /// `is_covered` is `false`
+    pub fn from_map(
+        map: &Map,
+        is_covered: impl Fn(Coordinates) -> bool,
+        is_flagged: impl Fn(Coordinates) -> bool,
+    ) -> Self {
+        let width = map.width();
+        let index = |c: Coordinates| c.y as usize * width as usize + c.x as usize;
+        let mut tiles = Vec::with_capacity(width as usize * map.height() as usize);
+        let mut neighbors = Vec::with_capacity(tiles.capacity());
+        for (coords, tile) in map.iter() {
+            let visible = if *tile == Tile::Void {
+                VisibleTile::Void
+            } else if is_covered(coords) {
+                if is_flagged(coords) {
+                    VisibleTile::Flagged
+                } else {
+                    VisibleTile::Covered
+                }
+            } else {
+                match tile {
+                    Tile::Bomb(_) => VisibleTile::Mine,
+                    Tile::BombNeighbor(v) => VisibleTile::Revealed(*v),
+                    Tile::Empty => VisibleTile::Revealed(0),
+                    Tile::Void => VisibleTile::Void,
+                }
+            };
+            tiles.push(visible);
+            neighbors.push(map.neighbors(coords).map(index).collect());
+        }
+        Self {
+            width,
+            height: map.height(),
+            bomb_count: map.bomb_count(),
+            tiles,
+            neighbors,
+        }
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Total number of bombs of the board
+    pub fn bomb_count(&self) -> u16 {
+        self.bomb_count
+    }
+
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+
+    pub fn tile(&self, index: usize) -> VisibleTile {
+        self.tiles[index]
+    }
+
+    pub fn tiles(&self) -> &[VisibleTile] {
+        &self.tiles
+    }
+
+    /// Indices of the neighbors of a tile
+    pub fn neighbors(&self, index: usize) -> &[usize] {
+        &self.neighbors[index]
+    }
+
+    pub fn index(&self, coords: Coordinates) -> usize {
+        coords.y as usize * self.width as usize + coords.x as usize
+    }
+
+    pub fn coords(&self, index: usize) -> Coordinates {
+        Coordinates {
+            x: (index % self.width as usize) as u16,
+            y: (index / self.width as usize) as u16,
+        }
+    }
+
+    /// Indices of the covered tiles, flagged or not
+    pub fn covered(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.tiles.len()).filter(|i| self.tiles[*i].is_covered())
+    }
+
+    /// Number of uncovered bombs
+    pub fn known_mines(&self) -> usize {
+        self.tiles
+            .iter()
+            .filter(|t| **t == VisibleTile::Mine)
+            .count()
+    }
+}
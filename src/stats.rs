@@ -0,0 +1,274 @@
+//! Local statistics and best times, persisted as RON
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use bevy::log;
+use bevy::prelude::Resource;
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::resources::difficulty::Difficulty;
+
+/// Number of best times kept per difficulty
+pub const BEST_TIMES_LEN: usize = 10;
+
+/// Speed and efficiency of a finished game
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct GameMetrics {
+    pub time: Duration,
+    /// 3BV of the board, see `Map::three_bv`
+    pub three_bv: u32,
+    /// Clicks made on the board
+    pub clicks: u32,
+}
+
+impl GameMetrics {
+    /// 3BV solved per second
+    pub fn three_bv_per_second(&self) -> f32 {
+        let secs = self.time.as_secs_f32();
+        if secs == 0. {
+            return 0.;
+        }
+        self.three_bv as f32 / secs
+    }
+
+    /// Ratio between the 3BV and the clicks made, above `1` when chording
+    /// saved clicks
+    pub fn efficiency(&self) -> f32 {
+        if self.clicks == 0 {
+            return 0.;
+        }
+        self.three_bv as f32 / self.clicks as f32
+    }
+}
+
+/// A winning game in the leaderboard
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BestTime {
+    pub time: Duration,
+    pub date: NaiveDate,
+    /// Seed of the board, to replay it
+    pub seed: u64,
+    #[serde(default)]
+    pub three_bv: u32,
+    #[serde(default)]
+    pub clicks: u32,
+}
+
+impl BestTime {
+    pub fn metrics(&self) -> GameMetrics {
+        GameMetrics {
+            time: self.time,
+            three_bv: self.three_bv,
+            clicks: self.clicks,
+        }
+    }
+}
+
+/// Statistics of a single difficulty
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DifficultyStats {
+    pub played: u32,
+    pub won: u32,
+    pub current_streak: u32,
+    pub best_streak: u32,
+    /// Fastest wins, sorted from the fastest
+    pub best_times: Vec<BestTime>,
+}
+
+impl DifficultyStats {
+    /// Ratio of won games, `0` when nothing was played
+    pub fn win_rate(&self) -> f32 {
+        if self.played == 0 {
+            return 0.;
+        }
+        self.won as f32 / self.played as f32
+    }
+
+    /// Records a win, returning its rank in the best times if it made it
+    pub fn record_win(&mut self, best_time: BestTime) -> Option<usize> {
+        self.played += 1;
+        self.won += 1;
+        self.current_streak += 1;
+        self.best_streak = self.best_streak.max(self.current_streak);
+        let rank = self
+            .best_times
+            .partition_point(|b| b.time <= best_time.time);
+        if rank >= BEST_TIMES_LEN {
+            return None;
+        }
+        self.best_times.insert(rank, best_time);
+        self.best_times.truncate(BEST_TIMES_LEN);
+        Some(rank)
+    }
+
+    pub fn record_loss(&mut self) {
+        self.played += 1;
+        self.current_streak = 0;
+    }
+}
+
+/// Statistics of every difficulty
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Stats {
+    pub difficulties: HashMap<Difficulty, DifficultyStats>,
+}
+
+impl Stats {
+    pub fn get(&self, difficulty: Difficulty) -> Option<&DifficultyStats> {
+        self.difficulties.get(&difficulty)
+    }
+
+    pub fn get_mut(&mut self, difficulty: Difficulty) -> &mut DifficultyStats {
+        self.difficulties.entry(difficulty).or_default()
+    }
+}
+
+/// Statistics resource along with the file it is saved to
+#[derive(Debug, Clone, Resource)]
+pub struct StatsStore {
+    pub path: PathBuf,
+    pub stats: Stats,
+}
+
+impl Default for StatsStore {
+    fn default() -> Self {
+        Self::load(Self::default_path())
+    }
+}
+
+impl StatsStore {
+    /// `stats.ron` in the platform data directory
+    pub fn default_path() -> PathBuf {
+        dirs::data_dir()
+            .unwrap_or_default()
+            .join("mine")
+            .join("stats.ron")
+    }
+
+    /// Loads the statistics, starting from scratch when the file is missing or invalid
+    pub fn load(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let stats = match read_ron(&path) {
+            Ok(stats) => stats,
+            Err(e) => {
+                if e.kind() != io::ErrorKind::NotFound {
+                    log::warn!("Failed to load stats from {:?}: {}", path, e);
+                }
+                Stats::default()
+            }
+        };
+        Self { path, stats }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        write_ron(&self.path, &self.stats)
+    }
+
+    /// Records a win of today and saves the statistics
+    pub fn record_win(&mut self, difficulty: Difficulty, metrics: GameMetrics, seed: u64) {
+        let best_time = BestTime {
+            time: metrics.time,
+            date: Local::now().date_naive(),
+            seed,
+            three_bv: metrics.three_bv,
+            clicks: metrics.clicks,
+        };
+        if let Some(rank) = self.stats.get_mut(difficulty).record_win(best_time) {
+            log::info!("New best time #{} in {}", rank + 1, difficulty.label());
+        }
+        self.save_or_log();
+    }
+
+    /// Records a loss and saves the statistics
+    pub fn record_loss(&mut self, difficulty: Difficulty) {
+        self.stats.get_mut(difficulty).record_loss();
+        self.save_or_log();
+    }
+
+    fn save_or_log(&self) {
+        if let Err(e) = self.save() {
+            log::error!("Failed to save stats to {:?}: {}", self.path, e);
+        }
+    }
+}
+
+/// Reads a RON file, parse errors being reported as `InvalidData`
+pub(crate) fn read_ron<T: for<'de> Deserialize<'de>>(path: &Path) -> io::Result<T> {
+    let content = fs::read_to_string(path)?;
+    ron::from_str(&content).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Writes a RON file, creating its parent directories
+pub(crate) fn write_ron<T: Serialize>(path: &Path, value: &T) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let content = ron::ser::to_string_pretty(value, Default::default())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    fs::write(path, content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn best_time(secs: u64) -> BestTime {
+        BestTime {
+            time: Duration::from_secs(secs),
+            date: NaiveDate::from_ymd_opt(2023, 9, 1).unwrap(),
+            seed: secs,
+            three_bv: 0,
+            clicks: 0,
+        }
+    }
+
+    #[test]
+    fn streaks_and_win_rate() {
+        let mut stats = DifficultyStats::default();
+        stats.record_win(best_time(30));
+        stats.record_win(best_time(20));
+        stats.record_loss();
+        stats.record_win(best_time(40));
+        assert_eq!(stats.played, 4);
+        assert_eq!(stats.won, 3);
+        assert_eq!(stats.current_streak, 1);
+        assert_eq!(stats.best_streak, 2);
+        assert_eq!(stats.win_rate(), 0.75);
+    }
+
+    #[test]
+    fn best_times_are_sorted_and_capped() {
+        let mut stats = DifficultyStats::default();
+        for secs in (1..=12).rev() {
+            stats.record_win(best_time(secs * 10));
+        }
+        assert_eq!(stats.best_times.len(), BEST_TIMES_LEN);
+        assert_eq!(stats.best_times[0].time, Duration::from_secs(10));
+        assert_eq!(stats.record_win(best_time(500)), None);
+        assert_eq!(stats.record_win(best_time(5)), Some(0));
+    }
+
+    #[test]
+    fn save_and_load() {
+        let path = std::env::temp_dir()
+            .join(format!("mine-stats-{}", std::process::id()))
+            .join("stats.ron");
+        let mut store = StatsStore::load(&path);
+        assert_eq!(store.stats, Stats::default());
+        let metrics = GameMetrics {
+            time: Duration::from_millis(91_250),
+            three_bv: 180,
+            clicks: 210,
+        };
+        store.record_win(Difficulty::Expert, metrics, 42);
+        store.record_loss(Difficulty::Beginner);
+
+        let loaded = StatsStore::load(&path);
+        assert_eq!(loaded.stats, store.stats);
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+}
@@ -1,6 +1,81 @@
+use std::time::Duration;
+
 use bevy::prelude::Event;
 
 use crate::components::coordinates::Coordinates;
+use crate::resources::tile::Tile;
+
+/// Asks the board to uncover the tile at the given coordinates
+#[derive(Debug, Clone, Copy, Event)]
+pub struct TileTriggerEvent(pub Coordinates);
+
+/// Asks the board to toggle the flag on the tile at the given coordinates
+#[derive(Debug, Clone, Copy, Event)]
+pub struct TileMarkEvent(pub Coordinates);
+
+/// Asks the board to uncover the unflagged neighbors of a revealed number
+/// once enough flags surround it
+#[derive(Debug, Clone, Copy, Event)]
+pub struct TileChordEvent(pub Coordinates);
+
+/// Asks the board to point out a tile worth playing
+#[derive(Debug, Clone, Copy, Event)]
+pub struct HintRequestEvent;
+
+/// Shows or hides the bomb probability of the covered tiles
+#[derive(Debug, Clone, Copy, Event)]
+pub struct OverlayToggleEvent;
+
+/// Asks the board to take back the last reveal or flag
+#[derive(Debug, Clone, Copy, Event)]
+pub struct UndoEvent;
+
+/// A tile has been uncovered
+#[derive(Debug, Clone, Copy, Event)]
+pub struct TileRevealed {
+    pub coords: Coordinates,
+    pub tile: Tile,
+}
+
+/// A flag has been placed on a covered tile
+#[derive(Debug, Clone, Copy, Event)]
+pub struct TileFlagged {
+    pub coords: Coordinates,
+}
+
+/// A flag has been removed from a covered tile
+#[derive(Debug, Clone, Copy, Event)]
+pub struct TileUnflagged {
+    pub coords: Coordinates,
+}
+
+/// The neighbors of a revealed number have been uncovered at once
+#[derive(Debug, Clone, Copy, Event)]
+pub struct Chorded {
+    pub coords: Coordinates,
+}
+
+/// The last action has been taken back, covering or unflagging tiles again
+#[derive(Debug, Clone, Copy, Event)]
+pub struct MoveUndone;
+
+/// A bomb has been uncovered
+#[derive(Debug, Clone, Copy, Event)]
+pub struct BombExploded {
+    pub coords: Coordinates,
+}
+
+/// A new board is ready to be played
+#[derive(Debug, Clone, Copy, Event)]
+pub struct GameStarted;
+
+/// Every safe tile has been uncovered
+#[derive(Debug, Clone, Copy, Event)]
+pub struct GameWon {
+    /// Time spent on the board
+    pub time: Duration,
+}
 
+/// The game ended on a bomb
 #[derive(Debug, Clone, Copy, Event)]
-pub struct TileTriggerEvent(pub Coordinates);
\ No newline at end of file
+pub struct GameLost;
@@ -0,0 +1,27 @@
+use bevy::prelude::*;
+
+use crate::components::ghost::{Ghost, GhostLayer};
+use crate::resources::board::{Board, TileMark};
+
+/// Mirrors the covers and marks of the edge tiles on their ghosts
+pub fn update_ghosts(board: Res<Board>, mut layers: Query<(&Ghost, &GhostLayer, &mut Visibility)>) {
+    if !board.is_changed() {
+        return;
+    }
+    for (ghost, layer, mut visibility) in layers.iter_mut() {
+        let visible = match layer {
+            GhostLayer::Cover => board.is_covered(&ghost.source),
+            // A single flag stands for flags of any number of mines
+            GhostLayer::Mark(mark) => matches!(
+                (board.mark(&ghost.source), mark),
+                (Some(TileMark::Flag(_)), TileMark::Flag(_))
+                    | (Some(TileMark::Question), TileMark::Question)
+            ),
+        };
+        *visibility = if visible {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
@@ -0,0 +1,144 @@
+use bevy::prelude::*;
+use bevy::log;
+use bevy::text::Text2dBounds;
+
+use crate::resources::board::Board;
+use crate::resources::board_asset::BoardAsset;
+use crate::solver::{self, Hint, HintKind, VisibleBoard};
+
+use super::event::{HintRequestEvent, MoveUndone, TileFlagged, TileRevealed, TileUnflagged};
+
+/// Hint currently shown on the board
+#[derive(Debug, Resource)]
+pub struct ActiveHint {
+    pub hint: Hint,
+    /// Highlighted tile cover and its original color
+    cover: Entity,
+    color: Color,
+    /// Explanation text entity
+    text: Entity,
+}
+
+pub fn show_hint(
+    mut commands: Commands,
+    mut board: ResMut<Board>,
+    board_asset: Res<BoardAsset>,
+    active_hint: Option<Res<ActiveHint>>,
+    mut sprites: Query<&mut Sprite>,
+    mut hint_request_event: EventReader<HintRequestEvent>,
+) {
+    if hint_request_event.iter().last().is_none() || !board.is_playing() {
+        return;
+    }
+    if !solver::supports(&board.tile_map) {
+        log::info!("No hint on boards with several mines per tile");
+        return;
+    }
+    if let Some(active) = active_hint {
+        clear(&mut commands, &active, &mut sprites);
+    }
+    let hint = match Hint::find(&VisibleBoard::from_board(&board)) {
+        Some(h) => h,
+        None => return,
+    };
+    log::info!("Hint: {}", hint.explanation);
+    let cover = match board.covered_tiles.get(&hint.coords) {
+        Some(e) => *e,
+        None => return,
+    };
+    // A hinted game stays off the statistics
+    board.assisted = true;
+    let mut color = Color::NONE;
+    if let Ok(mut sprite) = sprites.get_mut(cover) {
+        color = sprite.color;
+        sprite.color = match hint.kind {
+            HintKind::Safe => Color::GREEN,
+            HintKind::Mine => Color::RED,
+            HintKind::Guess { .. } => Color::ORANGE,
+        };
+    }
+    // Explanation displayed above the board
+    let size = board.bounds.size;
+    let text = commands
+        .spawn(Text2dBundle {
+            text: Text::from_section(
+                hint.explanation.clone(),
+                TextStyle {
+                    font: board_asset.bomb_counter_font.clone(),
+                    font_size: 10.,
+                    color: Color::WHITE,
+                },
+            )
+            .with_alignment(TextAlignment::Center),
+            text_2d_bounds: Text2dBounds {
+                size: Vec2::new(size.x.max(300.), f32::INFINITY),
+            },
+            transform: Transform::from_xyz(size.x / 2., size.y + 30., 5.),
+            ..Default::default()
+        })
+        .insert(Name::new("Hint"))
+        .id();
+    commands.entity(board.entity).add_child(text);
+    commands.insert_resource(ActiveHint {
+        hint,
+        cover,
+        color,
+        text,
+    });
+}
+
+/// Removes the hint as soon as the board changes
+pub fn clear_hint(
+    mut commands: Commands,
+    active_hint: Option<Res<ActiveHint>>,
+    mut sprites: Query<&mut Sprite>,
+    mut tile_revealed_event: EventReader<TileRevealed>,
+    mut tile_flagged_event: EventReader<TileFlagged>,
+    mut tile_unflagged_event: EventReader<TileUnflagged>,
+    mut move_undone_event: EventReader<MoveUndone>,
+) {
+    let changed = tile_revealed_event.iter().count()
+        + tile_flagged_event.iter().count()
+        + tile_unflagged_event.iter().count()
+        + move_undone_event.iter().count()
+        > 0;
+    if let Some(active) = active_hint {
+        if changed {
+            clear(&mut commands, &active, &mut sprites);
+        }
+    }
+}
+
+fn clear(commands: &mut Commands, active: &ActiveHint, sprites: &mut Query<&mut Sprite>) {
+    if let Ok(mut sprite) = sprites.get_mut(active.cover) {
+        sprite.color = active.color;
+    }
+    if let Some(text) = commands.get_entity(active.text) {
+        text.despawn_recursive();
+    }
+    commands.remove_resource::<ActiveHint>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resources::map::Map;
+
+    #[test]
+    fn a_hint_assists_the_game() {
+        let mut app = App::new();
+        let mut map = Map::empty(4, 4);
+        map.set_bombs(3, 1);
+        let board = Board::spawn_for_tests(&mut app.world, map);
+        app.insert_resource(board)
+            .insert_resource(BoardAsset::for_tests())
+            .add_event::<HintRequestEvent>()
+            .add_systems(Update, show_hint);
+        app.update();
+        assert!(!app.world.resource::<Board>().assisted);
+        app.world.send_event(HintRequestEvent);
+        app.update();
+        assert!(app.world.contains_resource::<ActiveHint>());
+        assert!(app.world.resource::<Board>().assisted);
+    }
+}
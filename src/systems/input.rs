@@ -4,13 +4,18 @@ use bevy::log;
 
 use crate::resources::board::Board;
 
-use super::event::TileTriggerEvent;
+use super::event::{
+    HintRequestEvent, OverlayToggleEvent, TileChordEvent, TileMarkEvent, TileTriggerEvent,
+    UndoEvent,
+};
 
 pub fn input_handler(
     window: Query<&Window>,
-    board: Option<Res<Board>>,
+    mut board: Option<ResMut<Board>>,
     mut button_event: EventReader<MouseButtonInput>,
-    mut tile_trigger_event: EventWriter<TileTriggerEvent>
+    mut tile_trigger_event: EventWriter<TileTriggerEvent>,
+    mut tile_mark_event: EventWriter<TileMarkEvent>,
+    mut tile_chord_event: EventWriter<TileChordEvent>,
 ) {
     let window = window.single();
 
@@ -20,23 +25,58 @@ pub fn input_handler(
             if let Some(pos) = position {
                 #[cfg(feature = "debug")]
                 log::info!("Mouse button pressed: {:?} at {}", event.button, pos);
-                if let Some(board) = &board {
+                if let Some(board) = &mut board {
+                    if !board.is_playing() {
+                        continue;
+                    }
                     let coordinates = board.mouse_position(window, pos);
                     if let Some(coor) = coordinates {
+                        // Every click on the board counts toward the efficiency
+                        board.clicks += 1;
                         match event.button {
-                            MouseButton::Left => {
+                            MouseButton::Left if board.is_covered(&coor) => {
                                 log::info!("Trying to uncover tile at: {:?}", coor);
                                 tile_trigger_event.send(TileTriggerEvent(coor));
                             },
+                            MouseButton::Left | MouseButton::Middle => {
+                                log::info!("Trying to chord tile at: {:?}", coor);
+                                tile_chord_event.send(TileChordEvent(coor));
+                            },
                             MouseButton::Right => {
                                 log::info!("Trying to flag bomb at: {:?}", coor);
+                                tile_mark_event.send(TileMarkEvent(coor));
                             },
-                            MouseButton::Middle => todo!(),
-                            MouseButton::Other(_) => todo!(),
+                            MouseButton::Other(_) => (),
                         }
                     }
                 }
             }
         }
     }
-}
\ No newline at end of file
+}
+
+pub fn keyboard_input_handler(
+    keys: Res<Input<KeyCode>>,
+    mut hint_request_event: EventWriter<HintRequestEvent>,
+    mut overlay_toggle_event: EventWriter<OverlayToggleEvent>,
+    mut undo_event: EventWriter<UndoEvent>,
+) {
+    if keys.just_pressed(KeyCode::H) {
+        log::info!("Asking for a hint");
+        hint_request_event.send(HintRequestEvent);
+    }
+    if keys.just_pressed(KeyCode::P) {
+        log::info!("Toggling the probability overlay");
+        overlay_toggle_event.send(OverlayToggleEvent);
+    }
+    if is_undo_shortcut(&keys) {
+        log::info!("Undoing the last move");
+        undo_event.send(UndoEvent);
+    }
+}
+
+/// Whether Ctrl+Z has just been pressed
+pub fn is_undo_shortcut(keys: &Input<KeyCode>) -> bool {
+    keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+        && keys.just_pressed(KeyCode::Z)
+}
@@ -0,0 +1,91 @@
+use bevy::prelude::*;
+use bevy::log;
+
+use crate::components::coordinates::Coordinates;
+use crate::resources::board::{Board, TileMark};
+use crate::resources::board_asset::BoardAsset;
+use crate::resources::board_options::BoardOptions;
+
+use super::event::{TileFlagged, TileMarkEvent, TileUnflagged};
+
+pub fn mark_tiles(
+    mut commands: Commands,
+    mut board: ResMut<Board>,
+    board_options: Res<BoardOptions>,
+    board_asset: Res<BoardAsset>,
+    mut tile_mark_event: EventReader<TileMarkEvent>,
+    mut tile_flagged_event: EventWriter<TileFlagged>,
+    mut tile_unflagged_event: EventWriter<TileUnflagged>,
+) {
+    for event in tile_mark_event.iter() {
+        let coords = event.0;
+        if !board.is_playing() || !board.is_covered(&coords) {
+            continue;
+        }
+        // Covered -> flag, once per mine a tile can hold -> question mark
+        // (when enabled) -> covered
+        let from = board.mark(&coords);
+        let to = match from {
+            None => Some(TileMark::Flag(1)),
+            Some(TileMark::Flag(mines)) if mines < board.tile_map.max_mines() => {
+                Some(TileMark::Flag(mines + 1))
+            }
+            Some(TileMark::Flag(_)) if board_options.marks => Some(TileMark::Question),
+            _ => None,
+        };
+        let size = board.tile_size - board_options.tile_padding;
+        set_mark(&mut commands, &mut board, &board_asset, size, coords, to);
+        log::info!("Marked tile {:?}: {:?} -> {:?}", coords, from, to);
+        board.history.record_mark(coords, from, to);
+        // Adding a mine to a flag keeps the tile flagged
+        let was_flagged = matches!(from, Some(TileMark::Flag(_)));
+        let is_flagged = matches!(to, Some(TileMark::Flag(_)));
+        if was_flagged && !is_flagged {
+            tile_unflagged_event.send(TileUnflagged { coords });
+        }
+        if is_flagged && !was_flagged {
+            tile_flagged_event.send(TileFlagged { coords });
+        }
+    }
+}
+
+/// Replaces the mark of a covered tile and its sprite
+pub fn set_mark(
+    commands: &mut Commands,
+    board: &mut Board,
+    board_asset: &BoardAsset,
+    size: f32,
+    coords: Coordinates,
+    mark: Option<TileMark>,
+) {
+    let previous = board
+        .flagged_tiles
+        .remove(&coords)
+        .map(|(entity, _)| entity)
+        .or_else(|| board.question_tiles.remove(&coords));
+    if let Some(entity) = previous {
+        commands.entity(entity).despawn_recursive();
+    }
+    let (Some(mark), Some(cover)) = (mark, board.covered_tiles.get(&coords).copied()) else {
+        return;
+    };
+    let entity = commands
+        .spawn(board_asset.mark_bundle(mark, size))
+        .insert(Name::new(format!("{:?}", mark)))
+        .id();
+    commands.entity(cover).add_child(entity);
+    match mark {
+        TileMark::Flag(mines) => {
+            if mines > 1 {
+                let count = commands
+                    .spawn(board_asset.mine_count_text_bundle(mines, size))
+                    .id();
+                commands.entity(entity).add_child(count);
+            }
+            board.flagged_tiles.insert(coords, (entity, mines));
+        }
+        TileMark::Question => {
+            board.question_tiles.insert(coords, entity);
+        }
+    }
+}
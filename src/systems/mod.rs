@@ -1,4 +1,10 @@
 pub mod event;
+pub mod ghost;
+pub mod hint;
 pub mod input;
+pub mod mark;
+pub mod overlay;
+pub mod timer;
 
-pub mod uncover;
\ No newline at end of file
+pub mod uncover;
+pub mod undo;
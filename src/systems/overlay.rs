@@ -0,0 +1,172 @@
+use bevy::log;
+use bevy::prelude::*;
+
+use crate::resources::board::Board;
+use crate::resources::board_asset::BoardAsset;
+use crate::solver::{self, Probabilities, VisibleBoard};
+
+use super::event::{
+    GameStarted, MoveUndone, OverlayToggleEvent, TileFlagged, TileRevealed, TileUnflagged,
+};
+
+const SAFE_COLOR: Color = Color::rgb(0.2, 0.8, 0.2);
+const MINE_COLOR: Color = Color::rgb(0.9, 0.1, 0.1);
+
+/// Bomb probability heatmap drawn over the covered tiles
+#[derive(Debug, Default, Resource)]
+pub struct ProbabilityOverlay {
+    pub enabled: bool,
+    /// Probabilities of the current board, while enabled
+    pub probabilities: Option<Probabilities>,
+    /// Percentage shown on the hovered tile
+    label: Option<Entity>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn update_overlay(
+    mut board: ResMut<Board>,
+    board_asset: Res<BoardAsset>,
+    mut overlay: ResMut<ProbabilityOverlay>,
+    mut sprites: Query<&mut Sprite>,
+    mut overlay_toggle_event: EventReader<OverlayToggleEvent>,
+    mut game_started_event: EventReader<GameStarted>,
+    mut tile_revealed_event: EventReader<TileRevealed>,
+    mut tile_flagged_event: EventReader<TileFlagged>,
+    mut tile_unflagged_event: EventReader<TileUnflagged>,
+    mut move_undone_event: EventReader<MoveUndone>,
+) {
+    let toggled = overlay_toggle_event.iter().count() % 2 == 1;
+    let changed = game_started_event.iter().count()
+        + tile_revealed_event.iter().count()
+        + tile_flagged_event.iter().count()
+        + tile_unflagged_event.iter().count()
+        + move_undone_event.iter().count()
+        > 0;
+    if !solver::supports(&board.tile_map) {
+        if toggled {
+            log::info!("No probabilities on boards with several mines per tile");
+        }
+        return;
+    }
+    if toggled {
+        overlay.enabled = !overlay.enabled;
+        // The exact probabilities keep the game off the statistics
+        if overlay.enabled {
+            board.assisted = true;
+        }
+    } else if !changed || !overlay.enabled {
+        return;
+    }
+    if !overlay.enabled {
+        overlay.probabilities = None;
+        for cover in board.covered_tiles.values() {
+            if let Ok(mut sprite) = sprites.get_mut(*cover) {
+                sprite.color = board_asset.covered_tile_material.color;
+            }
+        }
+        return;
+    }
+    let visible = VisibleBoard::from_board(&board);
+    let probabilities = Probabilities::compute(&visible);
+    for (coords, cover) in board.covered_tiles.iter() {
+        let p = probabilities.values[visible.index(*coords)].unwrap_or_default();
+        if let Ok(mut sprite) = sprites.get_mut(*cover) {
+            sprite.color = heat_color(p);
+        }
+    }
+    overlay.probabilities = Some(probabilities);
+}
+
+/// Shows the probability of the hovered covered tile
+pub fn hover_overlay(
+    mut commands: Commands,
+    window: Query<&Window>,
+    board: Res<Board>,
+    board_asset: Res<BoardAsset>,
+    mut overlay: ResMut<ProbabilityOverlay>,
+    mut labels: Query<(&mut Text, &mut Transform, &mut Visibility)>,
+) {
+    let window = window.single();
+    let hovered = window
+        .cursor_position()
+        .and_then(|pos| board.mouse_position(window, pos))
+        .filter(|coords| board.is_covered(coords));
+    let value = match (&overlay.probabilities, hovered) {
+        (Some(probabilities), Some(coords)) => {
+            let index = coords.y as usize * board.tile_map.width() as usize + coords.x as usize;
+            probabilities.values[index].map(|p| (coords, p))
+        }
+        _ => None,
+    };
+
+    let label = overlay
+        .label
+        .filter(|label| labels.contains(*label))
+        .unwrap_or_else(|| {
+            let label = commands
+                .spawn(Text2dBundle {
+                    text: Text::from_section(
+                        "",
+                        TextStyle {
+                            font: board_asset.bomb_counter_font.clone(),
+                            font_size: (board.tile_size / 3.).max(8.),
+                            color: Color::WHITE,
+                        },
+                    ),
+                    visibility: Visibility::Hidden,
+                    ..Default::default()
+                })
+                .insert(Name::new("Probability Label"))
+                .id();
+            commands.entity(board.entity).add_child(label);
+            label
+        });
+    overlay.label = Some(label);
+
+    if let Ok((mut text, mut transform, mut visibility)) = labels.get_mut(label) {
+        match value {
+            Some((coords, p)) => {
+                text.sections[0].value = format!("{:.0}%", p * 100.);
+                transform.translation = board.tile_translation(coords).extend(10.);
+                *visibility = Visibility::Inherited;
+            }
+            None => *visibility = Visibility::Hidden,
+        }
+    }
+}
+
+fn heat_color(p: f32) -> Color {
+    let [r0, g0, b0, _] = SAFE_COLOR.as_rgba_f32();
+    let [r1, g1, b1, _] = MINE_COLOR.as_rgba_f32();
+    Color::rgb(r0 + (r1 - r0) * p, g0 + (g1 - g0) * p, b0 + (b1 - b0) * p)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resources::map::Map;
+
+    #[test]
+    fn the_overlay_assists_the_game() {
+        let mut app = App::new();
+        let mut map = Map::empty(4, 4);
+        map.set_bombs(3, 1);
+        let board = Board::spawn_for_tests(&mut app.world, map);
+        app.insert_resource(board)
+            .insert_resource(BoardAsset::for_tests())
+            .init_resource::<ProbabilityOverlay>()
+            .add_event::<OverlayToggleEvent>()
+            .add_event::<GameStarted>()
+            .add_event::<TileRevealed>()
+            .add_event::<TileFlagged>()
+            .add_event::<TileUnflagged>()
+            .add_event::<MoveUndone>()
+            .add_systems(Update, update_overlay);
+        app.update();
+        assert!(!app.world.resource::<Board>().assisted);
+        app.world.send_event(OverlayToggleEvent);
+        app.update();
+        assert!(app.world.resource::<ProbabilityOverlay>().enabled);
+        assert!(app.world.resource::<Board>().assisted);
+    }
+}
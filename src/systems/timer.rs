@@ -0,0 +1,9 @@
+use bevy::prelude::*;
+
+use crate::resources::board::Board;
+
+pub fn tick_timer(time: Res<Time>, mut board: ResMut<Board>) {
+    if board.is_playing() {
+        board.timer.tick(time.delta());
+    }
+}
@@ -1,34 +1,81 @@
 use bevy::prelude::*;
 use bevy::log;
 
-use crate::resources::board::Board;
+use crate::resources::board::{Board, GameStatus};
+use crate::resources::tile::Tile;
 use crate::components::uncover::Uncover;
 use crate::components::bomb::Bomb;
 use crate::components::bomb_neighber::BombNeighbor;
 
-use super::event::TileTriggerEvent;
+use super::event::{
+    BombExploded, Chorded, GameLost, GameWon, TileChordEvent, TileRevealed, TileTriggerEvent,
+};
 
 pub fn trigger_event_handler(
     mut commands: Commands,
-    board: Res<Board>,
+    mut board: ResMut<Board>,
     mut tile_trigger_event: EventReader<TileTriggerEvent>
 ) {
     for event in tile_trigger_event.iter() {
         log::info!("event: {:?}", event);
-        if let Some(entity) = board.tile_to_uncover(&event.0) {
+        if !board.is_playing() {
+            continue;
+        }
+        if let Some(entity) = board.tile_to_uncover(&event.0).copied() {
             log::info!("entity: {:?}", entity);
-            commands.entity(*entity).insert(Uncover);
+            commands.entity(entity).insert(Uncover);
+            board.history.start_reveal();
         }
     }
 }
 
+pub fn chord_event_handler(
+    mut commands: Commands,
+    mut board: ResMut<Board>,
+    mut tile_chord_event: EventReader<TileChordEvent>,
+    mut chorded_event: EventWriter<Chorded>,
+) {
+    for event in tile_chord_event.iter() {
+        let coords = event.0;
+        if !board.is_playing() || board.is_covered(&coords) {
+            continue;
+        }
+        // Only a number surrounded by as many flags can be chorded
+        let count = match board.tile_map.get(coords) {
+            Some(Tile::BombNeighbor(v)) => *v as usize,
+            _ => continue,
+        };
+        if board.adjacent_flag_count(coords) != count {
+            continue;
+        }
+        let entities = board.adjacent_covered_tiles(coords);
+        if entities.is_empty() {
+            continue;
+        }
+        log::info!("Chording tile {:?}", coords);
+        for entity in entities {
+            commands.entity(entity).insert(Uncover);
+        }
+        board.history.start_reveal();
+        chorded_event.send(Chorded { coords });
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn uncover_tiles(
     mut commands: Commands,
     mut board: ResMut<Board>,
     children: Query<(Entity, &Parent), With<Uncover>>,
     parents: Query<(Option<&Bomb>, Option<&BombNeighbor>)>,
+    mut sprites: Query<&mut Sprite>,
+    mut tile_revealed_event: EventWriter<TileRevealed>,
+    mut bomb_exploded_event: EventWriter<BombExploded>,
+    mut game_won_event: EventWriter<GameWon>,
+    mut game_lost_event: EventWriter<GameLost>,
 ) {
+    let mut uncovered = false;
     for (entity, parent) in children.iter() {
+        uncovered = true;
         commands
             .entity(entity)
             .despawn_recursive();
@@ -49,13 +96,37 @@ pub fn uncover_tiles(
         };
 
         log::info!("query coodinates is {:?}", coords);
+        let mark = board.mark(&coords);
         match board.try_uncover_tile(&coords) {
-            Some(e) => log::debug!("Uncovered tile {:?} (entity: {:?})", coords, e),
+            Some(e) => {
+                log::debug!("Uncovered tile {:?} (entity: {:?})", coords, e);
+                board.history.record_reveal(coords, mark);
+            }
             None => log::debug!("Tried to uncover tile an already covered tile")
         }
+        if let Some(tile) = board.tile_map.get(coords) {
+            tile_revealed_event.send(TileRevealed { coords, tile: *tile });
+        }
 
         if bomb.is_some() {
             log::info!("Bomb !");
+            bomb_exploded_event.send(BombExploded { coords });
+            if board.is_playing() {
+                board.lives = board.lives.saturating_sub(1);
+                // The losing hit too, so that undoing it gives the life back
+                board.exploded_tiles.insert(coords);
+                if board.lives == 0 {
+                    board.status = GameStatus::Lost;
+                    game_lost_event.send(GameLost);
+                } else {
+                    log::info!("{} lives left", board.lives);
+                    // A forgiven mistake keeps the game off the leaderboards
+                    board.assisted = true;
+                    if let Ok(mut sprite) = sprites.get_mut(parent.get()) {
+                        sprite.color = Color::RED;
+                    }
+                }
+            }
         } else if bomb_counter.is_none() {
             log::info!("adjacent coverd tiles {:?}", board.adjacent_covered_tiles(coords));
             for entity in board.adjacent_covered_tiles(coords) {
@@ -63,5 +134,12 @@ pub fn uncover_tiles(
             }
         }
     }
-    
-}
\ No newline at end of file
+
+    if uncovered && board.is_playing() && board.is_completed() {
+        log::info!("Board completed in {:?}", board.timer.elapsed());
+        board.status = GameStatus::Won;
+        game_won_event.send(GameWon {
+            time: board.timer.elapsed(),
+        });
+    }
+}
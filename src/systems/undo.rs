@@ -0,0 +1,180 @@
+use bevy::log;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::components::coordinates::Coordinates;
+use crate::components::uncover::Uncover;
+use crate::resources::board::{Board, GameStatus};
+use crate::resources::board_asset::BoardAsset;
+use crate::resources::board_options::BoardOptions;
+use crate::resources::history::Action;
+
+use super::event::{MoveUndone, UndoEvent};
+use super::mark::set_mark;
+
+#[allow(clippy::too_many_arguments)]
+pub fn undo_move(
+    mut commands: Commands,
+    mut board: ResMut<Board>,
+    board_options: Res<BoardOptions>,
+    board_asset: Res<BoardAsset>,
+    tiles: Query<(Entity, &Coordinates)>,
+    mut sprites: Query<&mut Sprite>,
+    pending: Query<(), With<Uncover>>,
+    mut undo_event: EventReader<UndoEvent>,
+    mut move_undone_event: EventWriter<MoveUndone>,
+) {
+    for _ in undo_event.iter() {
+        // A won game is over for good
+        if board.status == GameStatus::Won {
+            continue;
+        }
+        // Wait for a flood fill to settle so it is undone as a whole
+        if !pending.is_empty() {
+            log::info!("Undo ignored while tiles are being uncovered");
+            continue;
+        }
+        let Some(action) = board.history.pop() else {
+            continue;
+        };
+        log::info!("Undoing {:?}", action);
+        let size = board.tile_size - board_options.tile_padding;
+        match action {
+            Action::Reveal(coords) => {
+                let tile_entities: HashMap<Coordinates, Entity> =
+                    tiles.iter().map(|(e, c)| (*c, e)).collect();
+                for (coords, mark) in coords {
+                    let Some(tile) = tile_entities.get(&coords) else {
+                        continue;
+                    };
+                    let cover = commands
+                        .spawn(board_asset.cover_bundle(board.tile_map.topology(), size))
+                        .insert(Name::new("Tile Cover"))
+                        .id();
+                    commands.entity(*tile).add_child(cover);
+                    board.covered_tiles.insert(coords, cover);
+                    if mark.is_some() {
+                        set_mark(&mut commands, &mut board, &board_asset, size, coords, mark);
+                    }
+                    // Taking back a hit bomb gives the life back
+                    if board.exploded_tiles.remove(&coords) {
+                        board.lives += 1;
+                        if let Ok(mut sprite) = sprites.get_mut(*tile) {
+                            sprite.color = board_asset.tile_material.color;
+                        }
+                    }
+                }
+                board.status = GameStatus::Playing;
+            }
+            Action::Mark { coords, from, .. } => {
+                set_mark(&mut commands, &mut board, &board_asset, size, coords, from);
+            }
+        }
+        board.assisted = true;
+        move_undone_event.send(MoveUndone);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resources::board::TileMark;
+    use crate::resources::map::Map;
+    use crate::systems::event::*;
+    use crate::systems::mark::mark_tiles;
+    use crate::systems::uncover::{trigger_event_handler, uncover_tiles};
+
+    fn app(map: Map) -> App {
+        let mut app = App::new();
+        let board = Board::spawn_for_tests(&mut app.world, map);
+        app.insert_resource(board)
+            .insert_resource(BoardAsset::for_tests())
+            .insert_resource(BoardOptions {
+                marks: true,
+                ..Default::default()
+            })
+            .add_event::<TileTriggerEvent>()
+            .add_event::<TileMarkEvent>()
+            .add_event::<TileRevealed>()
+            .add_event::<TileFlagged>()
+            .add_event::<TileUnflagged>()
+            .add_event::<BombExploded>()
+            .add_event::<GameWon>()
+            .add_event::<GameLost>()
+            .add_event::<UndoEvent>()
+            .add_event::<MoveUndone>()
+            .add_systems(
+                Update,
+                (
+                    trigger_event_handler,
+                    mark_tiles,
+                    apply_deferred,
+                    uncover_tiles,
+                    apply_deferred,
+                    undo_move,
+                )
+                    .chain(),
+            );
+        app
+    }
+
+    #[test]
+    fn undo_restores_question_marks() {
+        let bombs = [(0, 0), (2, 2)].map(Coordinates::from);
+        let mut app = app(Map::with_bombs(3, 3, bombs));
+        let coords = Coordinates::new(1, 1);
+        // Flag, question mark, then back to covered
+        for _ in 0..3 {
+            app.world.send_event(TileMarkEvent(coords));
+            app.update();
+        }
+        app.world.send_event(UndoEvent);
+        app.update();
+        let board = app.world.resource::<Board>();
+        assert_eq!(board.mark(&coords), Some(TileMark::Question));
+        // Uncovering the unsure tile then undoing puts the mark back
+        app.world.send_event(TileTriggerEvent(coords));
+        app.update();
+        assert!(!app.world.resource::<Board>().is_covered(&coords));
+        app.world.send_event(UndoEvent);
+        app.update();
+        let board = app.world.resource::<Board>();
+        assert!(board.is_covered(&coords));
+        assert_eq!(board.mark(&coords), Some(TileMark::Question));
+    }
+
+    #[test]
+    fn the_safe_start_cannot_be_undone() {
+        let mut app = app(Map::with_bombs(3, 3, [Coordinates::new(2, 2)]));
+        let start = Coordinates::new(1, 1);
+        let cover = app.world.resource::<Board>().covered_tiles[&start];
+        app.world.entity_mut(cover).insert(Uncover);
+        app.update();
+        app.world.send_event(UndoEvent);
+        app.update();
+        let board = app.world.resource::<Board>();
+        assert!(!board.is_covered(&start));
+        assert!(!board.assisted);
+    }
+
+    #[test]
+    fn undo_gives_every_life_back() {
+        let bombs = [(0, 0), (2, 0), (0, 2)].map(Coordinates::from);
+        let mut app = app(Map::with_bombs(3, 3, bombs));
+        app.world.resource_mut::<Board>().lives = 3;
+        for coords in bombs {
+            app.world.send_event(TileTriggerEvent(coords));
+            app.update();
+        }
+        let board = app.world.resource::<Board>();
+        assert_eq!(board.status, GameStatus::Lost);
+        assert_eq!(board.lives, 0);
+        for lives in [1, 2] {
+            app.world.send_event(UndoEvent);
+            app.update();
+            let board = app.world.resource::<Board>();
+            assert_eq!(board.status, GameStatus::Playing);
+            assert_eq!(board.lives, lives);
+        }
+    }
+}